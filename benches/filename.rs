@@ -0,0 +1,27 @@
+//! Measures `tags_from_filename` across plain, special and deeply-dotted names.
+//!
+//! Run with `cargo bench --bench filename`.
+
+use file_identify::tags_from_filename;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: usize = 200_000;
+
+fn bench(label: &str, filename: &str) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(tags_from_filename(black_box(filename)));
+    }
+    let per_call = start.elapsed() / ITERATIONS as u32;
+    println!("{label:<16} {filename:<32} {per_call:>10.2?}/call");
+}
+
+fn main() {
+    bench("extension", "main.rs");
+    bench("special name", "Makefile");
+    bench("name component", "Dockerfile.xenial");
+    bench("dotted", "setup.cfg");
+    bench("deeply dotted", "a.b.c.d.e.f.g.h.i.j.k.l");
+    bench("unrecognized", "unknown");
+}
@@ -0,0 +1,64 @@
+//! Compares allocations and wall time of `identify_many` against identifying
+//! each in-memory blob separately with `identify_parts`.
+//!
+//! Run with `cargo bench --bench identify_many`.
+
+use file_identify::FileIdentifier;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn blobs(count: usize) -> Vec<(String, Vec<u8>)> {
+    (0..count)
+        .map(|i| {
+            let content = format!("line {i}\n").repeat(200).into_bytes();
+            (format!("blob-{i}"), content)
+        })
+        .collect()
+}
+
+fn measure<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!("{label:<16} {allocations:>8} allocations {elapsed:>12.2?}");
+    result
+}
+
+fn main() {
+    const COUNT: usize = 10_000;
+    let identifier = FileIdentifier::new();
+
+    let items = blobs(COUNT);
+    let individual = measure("identify_parts", || {
+        items
+            .iter()
+            .map(|(name, content)| identifier.identify_parts(name, false, content.as_slice()))
+            .collect::<Vec<_>>()
+    });
+
+    let items = blobs(COUNT);
+    let batched = measure("identify_many", || identifier.identify_many(items));
+
+    assert_eq!(individual.len(), batched.len());
+}
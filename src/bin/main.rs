@@ -1,5 +1,9 @@
 use clap::Parser;
+#[cfg(feature = "config")]
+use file_identify::FileIdentifier;
 use file_identify::{tags_from_filename, tags_from_path};
+#[cfg(feature = "config")]
+use std::path::PathBuf;
 use std::process;
 
 #[derive(Parser)]
@@ -13,6 +17,15 @@ struct Args {
     #[arg(long)]
     filename_only: bool,
 
+    /// Terminate output with a NUL byte instead of a newline (for `xargs -0`)
+    #[arg(long)]
+    print0: bool,
+
+    /// Load identification settings from a TOML configuration file
+    #[cfg(feature = "config")]
+    #[arg(long, value_name = "PATH", conflicts_with = "filename_only")]
+    config: Option<PathBuf>,
+
     /// Path to the file to identify
     path: String,
 }
@@ -20,6 +33,25 @@ struct Args {
 fn main() {
     let args = Args::parse();
 
+    #[cfg(feature = "config")]
+    if let Some(config_path) = &args.config {
+        let identifier = match load_config(config_path) {
+            Ok(identifier) => identifier,
+            Err(e) => {
+                eprintln!("{}: {e}", config_path.display());
+                process::exit(1);
+            }
+        };
+        match identifier.identify(&args.path) {
+            Ok(tags) => print_tags(&tags, args.print0),
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
     let tags = if args.filename_only {
         tags_from_filename(&args.path)
     } else {
@@ -32,6 +64,16 @@ fn main() {
         }
     };
 
+    print_tags(&tags, args.print0);
+}
+
+#[cfg(feature = "config")]
+fn load_config(path: &std::path::Path) -> Result<FileIdentifier, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(FileIdentifier::from_config_str(&contents)?)
+}
+
+fn print_tags(tags: &file_identify::tags::TagSet, print0: bool) {
     if tags.is_empty() {
         process::exit(1);
     }
@@ -42,6 +84,7 @@ fn main() {
 
     // Output as JSON array (matching Python version behavior)
     match serde_json::to_string(&sorted_tags) {
+        Ok(json) if print0 => print!("{json}\0"),
         Ok(json) => println!("{json}"),
         Err(_) => process::exit(1),
     }
@@ -0,0 +1,211 @@
+//! Loading [`FileIdentifier`] settings from a TOML configuration file.
+
+use crate::tags::TagSet;
+use crate::{ContentErrorPolicy, ExecutablePolicy, FileIdentifier, IdentifyError, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// The on-disk configuration format.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    extensions: HashMap<String, Vec<String>>,
+    interpreters: HashMap<String, Vec<String>>,
+    skip_content_analysis: bool,
+    skip_shebang_analysis: bool,
+    skip_permission_analysis: bool,
+    max_content_bytes: Option<u64>,
+    content_sniff_bytes: Option<usize>,
+    always_read_content: bool,
+    flag_conflicts: bool,
+    detect_script: bool,
+    detect_modelines: bool,
+    validate_utf8: bool,
+    treat_blank_as_empty: bool,
+    fast_language_only: bool,
+    tag_unknown: bool,
+    tag_hidden: bool,
+    treat_special_as_file: bool,
+    tag_unknown_shebang_as_script: bool,
+    resolve_polyglot_shebangs: bool,
+    resolve_node_module_type: bool,
+    executable_policy: Option<ExecutablePolicy>,
+    on_content_error: Option<ContentErrorPolicy>,
+    max_symlink_depth: Option<usize>,
+}
+
+impl FileIdentifier {
+    /// Build a file identifier from a TOML configuration string.
+    ///
+    /// The configuration may contain `[extensions]` and `[interpreters]`
+    /// tables mapping names to tag lists, and the boolean flags matching the
+    /// builder methods (`skip_content_analysis`, `flag_conflicts`, ...), plus
+    /// `max_content_bytes`, `content_sniff_bytes` and `max_symlink_depth`.
+    /// `executable_policy` and `on_content_error` take the policy variant in
+    /// kebab case, e.g. `"shebang-presence"` or `"assume-binary"`. Omitted
+    /// settings keep their defaults.
+    ///
+    /// Extension keys are matched case-insensitively, like
+    /// [`with_custom_extensions`](Self::with_custom_extensions); interpreter
+    /// keys are matched exactly, like the built-in interpreter names.
+    ///
+    /// Requires the `config` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdentifyError::InvalidConfig`] if the configuration is not
+    /// valid TOML or contains unknown keys.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::FileIdentifier;
+    ///
+    /// let identifier = FileIdentifier::from_config_str(r#"
+    ///     skip_shebang_analysis = true
+    ///
+    ///     [extensions]
+    ///     tpl = ["text", "template"]
+    /// "#).unwrap();
+    /// # let _ = identifier;
+    /// ```
+    pub fn from_config_str(toml: &str) -> Result<Self> {
+        let config: Config = toml::from_str(toml).map_err(|e| IdentifyError::InvalidConfig {
+            message: e.to_string(),
+        })?;
+
+        let mut identifier = FileIdentifier::new();
+        identifier.skip_content_analysis = config.skip_content_analysis;
+        identifier.skip_shebang_analysis = config.skip_shebang_analysis;
+        identifier.skip_permission_analysis = config.skip_permission_analysis;
+        identifier.max_content_bytes = config.max_content_bytes;
+        if let Some(bytes) = config.content_sniff_bytes {
+            identifier = identifier.content_sniff_bytes(bytes);
+        }
+        identifier.always_read_content = config.always_read_content;
+        identifier.flag_conflicts = config.flag_conflicts;
+        identifier.detect_script = config.detect_script;
+        identifier.detect_modelines = config.detect_modelines;
+        identifier.validate_utf8 = config.validate_utf8;
+        identifier.treat_blank_as_empty = config.treat_blank_as_empty;
+        identifier.fast_language_only = config.fast_language_only;
+        identifier.tag_unknown = config.tag_unknown;
+        identifier.tag_hidden = config.tag_hidden;
+        identifier.treat_special_as_file = config.treat_special_as_file;
+        identifier.tag_unknown_shebang_as_script = config.tag_unknown_shebang_as_script;
+        identifier.resolve_polyglot_shebangs = config.resolve_polyglot_shebangs;
+        identifier.resolve_node_module_type = config.resolve_node_module_type;
+        identifier.executable_policy = config.executable_policy.unwrap_or_default();
+        identifier.content_error_policy = config.on_content_error.unwrap_or_default();
+        identifier.max_symlink_depth = config.max_symlink_depth;
+
+        if !config.extensions.is_empty() {
+            identifier = identifier.with_custom_extensions(intern_tag_map(config.extensions));
+        }
+        if !config.interpreters.is_empty() {
+            identifier.custom_interpreters = Some(intern_tag_map(config.interpreters));
+        }
+
+        Ok(identifier)
+    }
+}
+
+fn intern_tag_map(map: HashMap<String, Vec<String>>) -> HashMap<String, TagSet> {
+    map.into_iter()
+        .map(|(key, tags)| {
+            let tags = tags.iter().map(|tag| intern_tag(tag)).collect();
+            (key, tags)
+        })
+        .collect()
+}
+
+/// Get a `'static` copy of a tag read from configuration.
+///
+/// Each distinct tag is leaked once and reused afterwards, so loading
+/// configurations repeatedly does not grow memory without bound.
+fn intern_tag(tag: &str) -> &'static str {
+    static INTERNED: Mutex<Option<HashSet<&'static str>>> = Mutex::new(None);
+
+    let mut interned = INTERNED.lock().unwrap_or_else(|e| e.into_inner());
+    let interned = interned.get_or_insert_with(HashSet::new);
+    if let Some(&existing) = interned.get(tag) {
+        return existing;
+    }
+
+    let leaked: &'static str = Box::leak(tag.to_owned().into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_from_config_str() {
+        let identifier = FileIdentifier::from_config_str(
+            r#"
+            flag_conflicts = true
+            executable_policy = "shebang-presence"
+            on_content_error = "assume-binary"
+            max_symlink_depth = 8
+
+            [extensions]
+            TPL = ["text", "template"]
+
+            [interpreters]
+            mytool = ["mytool-script"]
+            MyTool = ["mixed-case-script"]
+            "#,
+        )
+        .unwrap();
+        assert!(identifier.flag_conflicts);
+        assert!(!identifier.skip_content_analysis);
+        assert_eq!(
+            identifier.executable_policy,
+            ExecutablePolicy::ShebangPresence
+        );
+        assert_eq!(
+            identifier.content_error_policy,
+            ContentErrorPolicy::AssumeBinary
+        );
+        assert_eq!(identifier.max_symlink_depth, Some(8));
+
+        let tags = identifier
+            .identify_parts("page.tpl", false, Cursor::new(b"{{ title }}"))
+            .unwrap();
+        assert!(tags.contains("template"));
+        assert!(tags.contains("text"));
+
+        let tags = identifier
+            .identify_parts("run", true, Cursor::new(b"#!/opt/bin/mytool\n"))
+            .unwrap();
+        assert!(tags.contains("mytool-script"));
+
+        let tags = identifier
+            .identify_parts("run", true, Cursor::new(b"#!/opt/bin/MyTool\n"))
+            .unwrap();
+        assert!(tags.contains("mixed-case-script"));
+    }
+
+    #[test]
+    fn test_from_config_str_rejects_invalid_config() {
+        let err = FileIdentifier::from_config_str("skip_everything = true").unwrap_err();
+        assert!(matches!(err, IdentifyError::InvalidConfig { .. }));
+
+        let err = FileIdentifier::from_config_str("executable_policy = \"sometimes\"").unwrap_err();
+        assert!(matches!(err, IdentifyError::InvalidConfig { .. }));
+
+        let err = FileIdentifier::from_config_str("[extensions").unwrap_err();
+        assert!(matches!(err, IdentifyError::InvalidConfig { .. }));
+    }
+
+    #[test]
+    fn test_intern_tag_reuses_strings() {
+        let first = intern_tag("interned-tag");
+        let second = intern_tag(&String::from("interned-tag"));
+        assert!(std::ptr::eq(first, second));
+    }
+}
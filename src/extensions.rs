@@ -1,5 +1,6 @@
 use crate::tags::{TagSet, tags_from_array};
 use phf::phf_map;
+use std::path::Path;
 
 pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map! {
     "adoc" => &["text", "asciidoc"],
@@ -8,10 +9,11 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "asciidoc" => &["text", "asciidoc"],
     "apinotes" => &["text", "apinotes"],
     "asar" => &["binary", "asar"],
-    "asm" => &["text", "asm"],
+    "asm" => &["text", "asm", "assembly"],
     "astro" => &["text", "astro"],
     "avif" => &["binary", "image", "avif"],
-    "avsc" => &["text", "avro-schema"],
+    "avro" => &["binary", "avro"],
+    "avsc" => &["text", "json", "avro-schema"],
     "bash" => &["text", "shell", "bash"],
     "bat" => &["text", "batch"],
     "bats" => &["text", "shell", "bash", "bats"],
@@ -24,15 +26,16 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "bmp" => &["binary", "image", "bitmap"],
     "bz2" => &["binary", "bzip2"],
     "bz3" => &["binary", "bzip3"],
-    "bzl" => &["text", "bazel"],
+    "bzl" => &["text", "bazel", "starlark"],
     "c" => &["text", "c"],
     "c++" => &["text", "c++"],
     "c++m" => &["text", "c++"],
+    "capnp" => &["text", "capnproto"],
     "cc" => &["text", "c++"],
     "ccm" => &["text", "c++"],
     "cfg" => &["text"],
     "chs" => &["text", "c2hs"],
-    "cjs" => &["text", "javascript"],
+    "cjs" => &["text", "javascript", "commonjs"],
     "clj" => &["text", "clojure"],
     "cljc" => &["text", "clojure"],
     "cljs" => &["text", "clojure", "clojurescript"],
@@ -43,7 +46,6 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "cpp" => &["text", "c++"],
     "cppm" => &["text", "c++"],
     "cr" => &["text", "crystal"],
-    "crt" => &["text", "pem"],
     "cs" => &["text", "c#"],
     "csproj" => &["text", "xml", "csproj", "msbuild"],
     "csh" => &["text", "shell", "csh"],
@@ -51,24 +53,30 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "css" => &["text", "css"],
     "csv" => &["text", "csv"],
     "csx" => &["text", "c#", "c#script"],
+    "cts" => &["text", "ts", "commonjs"],
     "cu" => &["text", "cuda"],
     "cue" => &["text", "cue"],
     "cuh" => &["text", "cuda"],
     "cxx" => &["text", "c++"],
     "cxxm" => &["text", "c++"],
     "cylc" => &["text", "cylc"],
+    "cypher" => &["text", "cypher"],
     "dart" => &["text", "dart"],
     "dbc" => &["text", "dbc"],
     "def" => &["text", "def"],
-    "dll" => &["binary"],
+    "der" => &["binary", "der"],
+    "desktop" => &["text", "ini", "desktop"],
+    "dll" => &["binary", "shared-library"],
+    "doc" => &["binary", "msoffice"],
     "dtd" => &["text", "dtd"],
+    "dylib" => &["binary", "shared-library"],
     "ear" => &["binary", "zip", "jar"],
     "edn" => &["text", "clojure", "edn"],
     "ejs" => &["text", "ejs"],
     "ejson" => &["text", "json", "ejson"],
     "elm" => &["text", "elm"],
     "env" => &["text", "dotenv"],
-    "eot" => &["binary", "eot"],
+    "eot" => &["binary", "font", "eot"],
     "eps" => &["binary", "eps"],
     "erb" => &["text", "erb"],
     "erl" => &["text", "erlang"],
@@ -80,6 +88,7 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "f08" => &["text", "fortran"],
     "f90" => &["text", "fortran"],
     "f95" => &["text", "fortran"],
+    "fbs" => &["text", "flatbuffers"],
     "feature" => &["text", "gherkin"],
     "fish" => &["text", "fish"],
     "fits" => &["binary", "fits"],
@@ -95,6 +104,7 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "go" => &["text", "go"],
     "gotmpl" => &["text", "gotmpl"],
     "gpx" => &["text", "gpx", "xml"],
+    "gql" => &["text", "graphql"],
     "graphql" => &["text", "graphql"],
     "gradle" => &["text", "groovy"],
     "groovy" => &["text", "groovy"],
@@ -105,6 +115,8 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "h" => &["text", "header", "c", "c++"],
     "hbs" => &["text", "handlebars"],
     "hcl" => &["text", "hcl"],
+    "heic" => &["binary", "image", "heic"],
+    "heif" => &["binary", "image", "heif"],
     "hh" => &["text", "header", "c++"],
     "hpp" => &["text", "header", "c++"],
     "hrl" => &["text", "erlang"],
@@ -137,12 +149,15 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "jpg" => &["binary", "image", "jpeg"],
     "js" => &["text", "javascript"],
     "json" => &["text", "json"],
+    "jsonc" => &["text", "jsonc", "json"],
     "jsonld" => &["text", "json", "jsonld"],
     "jsonnet" => &["text", "jsonnet"],
     "json5" => &["text", "json5"],
-    "jsx" => &["text", "jsx"],
+    "jsx" => &["text", "jsx", "javascript"],
+    "jxl" => &["binary", "image", "jxl"],
     "key" => &["text", "pem"],
     "kml" => &["text", "kml", "xml"],
+    "ksh" => &["text", "shell", "ksh"],
     "kt" => &["text", "kotlin"],
     "kts" => &["text", "kotlin"],
     "lean" => &["text", "lean"],
@@ -153,6 +168,7 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "libsonnet" => &["text", "jsonnet"],
     "lidr" => &["text", "idris"],
     "liquid" => &["text", "liquid"],
+    "ll" => &["text", "llvm"],
     "lpi" => &["text", "lazarus", "xml"],
     "lpr" => &["text", "lazarus", "pascal"],
     "lr" => &["text", "lektor"],
@@ -165,18 +181,21 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "map" => &["text", "map"],
     "markdown" => &["text", "markdown"],
     "md" => &["text", "markdown"],
-    "mdx" => &["text", "mdx"],
+    "mdown" => &["text", "markdown"],
+    "mdx" => &["text", "markdown", "mdx"],
     "meson" => &["text", "meson"],
     "metal" => &["text", "metal"],
     "mib" => &["text", "mib"],
-    "mjs" => &["text", "javascript"],
+    "mjs" => &["text", "javascript", "module"],
     "mk" => &["text", "makefile"],
+    "mkd" => &["text", "markdown"],
     "ml" => &["text", "ocaml"],
     "mli" => &["text", "ocaml"],
     "mm" => &["text", "c++", "objective-c++"],
     "modulemap" => &["text", "modulemap"],
     "mscx" => &["text", "xml", "musescore"],
     "mscz" => &["binary", "zip", "musescore"],
+    "mts" => &["text", "ts", "module"],
     "mustache" => &["text", "mustache"],
     "myst" => &["text", "myst"],
     "ngdoc" => &["text", "ngdoc"],
@@ -185,12 +204,18 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "nimble" => &["text", "nimble"],
     "nix" => &["text", "nix"],
     "njk" => &["text", "nunjucks"],
-    "otf" => &["binary", "otf"],
+    "nomad" => &["text", "hcl", "nomad"],
+    "odp" => &["binary", "opendocument"],
+    "ods" => &["binary", "opendocument"],
+    "odt" => &["binary", "opendocument"],
+    "otf" => &["binary", "font", "otf", "opentype"],
     "p12" => &["binary", "p12"],
+    "parquet" => &["binary", "parquet"],
     "pas" => &["text", "pascal"],
     "patch" => &["text", "diff"],
     "pdf" => &["binary", "pdf"],
     "pem" => &["text", "pem"],
+    "pfx" => &["binary", "p12"],
     "php" => &["text", "php"],
     "php4" => &["text", "php"],
     "php5" => &["text", "php"],
@@ -202,10 +227,11 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "po" => &["text", "pofile"],
     "pom" => &["pom", "text", "xml"],
     "pp" => &["text", "puppet"],
+    "ppt" => &["binary", "msoffice"],
     "prisma" => &["text", "prisma"],
     "properties" => &["text", "java-properties"],
     "props" => &["text", "xml", "msbuild"],
-    "proto" => &["text", "proto"],
+    "proto" => &["text", "proto", "protobuf"],
     "ps1" => &["text", "powershell"],
     "psd1" => &["text", "powershell"],
     "psm1" => &["text", "powershell"],
@@ -215,7 +241,9 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "pxd" => &["text", "cython"],
     "pxi" => &["text", "cython"],
     "py" => &["text", "python"],
+    "pyc" => &["binary", "python-bytecode"],
     "pyi" => &["text", "pyi"],
+    "pyo" => &["binary", "python-bytecode"],
     "pyproj" => &["text", "xml", "pyproj", "msbuild"],
     "pyt" => &["text", "python"],
     "pyx" => &["text", "cython"],
@@ -229,7 +257,7 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "rng" => &["text", "xml", "relax-ng"],
     "rs" => &["text", "rust"],
     "rst" => &["text", "rst"],
-    "s" => &["text", "asm"],
+    "s" => &["text", "asm", "assembly"],
     "sas" => &["text", "sas"],
     "sass" => &["text", "sass"],
     "sbt" => &["text", "sbt", "scala"],
@@ -237,14 +265,20 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "scala" => &["text", "scala"],
     "scm" => &["text", "scheme"],
     "scss" => &["text", "scss"],
+    "service" => &["text", "ini", "systemd"],
     "sh" => &["text", "shell"],
     "sln" => &["text", "sln"],
     "sls" => &["text", "salt"],
-    "so" => &["binary"],
+    "so" => &["binary", "shared-library"],
+    "socket" => &["text", "ini", "systemd"],
     "sol" => &["text", "solidity"],
+    "sparql" => &["text", "sparql"],
     "spec" => &["text", "spec"],
     "sql" => &["text", "sql"],
+    "sqlite" => &["binary", "sqlite"],
+    "sqlite3" => &["binary", "sqlite"],
     "ss" => &["text", "scheme"],
+    "strings" => &["text", "strings"],
     "sty" => &["text", "tex"],
     "styl" => &["text", "stylus"],
     "sv" => &["text", "system-verilog"],
@@ -260,16 +294,18 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "templ" => &["text", "templ"],
     "tex" => &["text", "tex"],
     "textproto" => &["text", "textproto"],
-    "tf" => &["text", "terraform"],
-    "tfvars" => &["text", "terraform"],
+    "tf" => &["text", "terraform", "hcl"],
+    "tfstate" => &["text", "json"],
+    "tfvars" => &["text", "terraform", "hcl"],
     "tgz" => &["binary", "gzip"],
     "thrift" => &["text", "thrift"],
     "tiff" => &["binary", "image", "tiff"],
+    "timer" => &["text", "ini", "systemd"],
     "toml" => &["text", "toml"],
     "ts" => &["text", "ts"],
     "tsv" => &["text", "tsv"],
-    "tsx" => &["text", "tsx"],
-    "ttf" => &["binary", "ttf"],
+    "tsx" => &["text", "tsx", "ts"],
+    "ttf" => &["binary", "font", "ttf", "truetype"],
     "twig" => &["text", "twig"],
     "txsprofile" => &["text", "ini", "txsprofile"],
     "txt" => &["text", "plain-text"],
@@ -286,17 +322,22 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
     "vtl" => &["text", "vtl"],
     "vue" => &["text", "vue"],
     "war" => &["binary", "zip", "jar"],
+    "wasm" => &["binary", "wasm"],
+    "wat" => &["text", "wasm", "wat"],
     "wav" => &["binary", "audio", "wav"],
+    "webmanifest" => &["text", "json"],
     "webp" => &["binary", "image", "webp"],
     "whl" => &["binary", "wheel", "zip"],
     "wkt" => &["text", "wkt"],
-    "woff" => &["binary", "woff"],
-    "woff2" => &["binary", "woff2"],
+    "woff" => &["binary", "font", "woff"],
+    "woff2" => &["binary", "font", "woff2"],
     "wsdl" => &["text", "xml", "wsdl"],
     "wsgi" => &["text", "wsgi", "python"],
+    "xcconfig" => &["text", "xcconfig"],
     "xhtml" => &["text", "xml", "html", "xhtml"],
     "xacro" => &["text", "xml", "urdf", "xacro"],
     "xctestplan" => &["text", "json"],
+    "xls" => &["binary", "msoffice"],
     "xml" => &["text", "xml"],
     "xq" => &["text", "xquery"],
     "xql" => &["text", "xquery"],
@@ -320,14 +361,20 @@ pub static EXTENSION_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf
 };
 
 pub static EXTENSIONS_NEED_BINARY_CHECK_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map! {
+    // Certificates may be PEM text or DER binary
+    "cer" => &["certificate"],
+    "crt" => &["certificate"],
+    // Property lists may be XML text or binary
+    "entitlements" => &["plist", "entitlements"],
+    "mobileconfig" => &["plist", "mobileconfig"],
     "plist" => &["plist"],
     "ppm" => &["image", "ppm"],
 };
 
 pub static NAME_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map! {
     ".ansible-lint" => &["text", "yaml"],
-    ".clang-format" => &["text", "yaml"],
-    ".clang-tidy" => &["text", "yaml"],
+    ".clang-format" => &["text", "yaml", "clang-format"],
+    ".clang-tidy" => &["text", "yaml", "clang-tidy"],
     ".salt-lint" => &["text", "yaml", "salt-lint"],
     ".yamllint" => &["text", "yaml", "yamllint"],
     ".babelrc" => &["text", "json", "babelrc"],
@@ -356,6 +403,8 @@ pub static NAME_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map!
     ".zprofile" => &["text", "shell", "zsh"],
     ".zshrc" => &["text", "shell", "zsh"],
     ".zshenv" => &["text", "shell", "zsh"],
+    ".yarnrc" => &["text", "yarnrc"],
+    ".yarnrc.yml" => &["text", "yaml", "yarnrc"],
     "direnvrc" => &["text", "shell", "bash"],
     ".codespellrc" => &["text", "ini", "codespellrc"],
     ".coveragerc" => &["text", "ini", "coveragerc"],
@@ -364,6 +413,7 @@ pub static NAME_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map!
     ".gitlint" => &["text", "ini", "gitlint"],
     ".hgrc" => &["text", "ini", "hgrc"],
     ".isort.cfg" => &["text", "ini", "isort"],
+    ".npmrc" => &["text", "ini", "npmrc"],
     ".pypirc" => &["text", "ini", "pypirc"],
     ".rstcheck.cfg" => &["text", "ini"],
     ".sqlfluff" => &["text", "ini"],
@@ -377,7 +427,8 @@ pub static NAME_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map!
     ".prettierignore" => &["text", "gitignore", "prettierignore"],
     ".bazelrc" => &["text", "bazelrc"],
     ".browserslistrc" => &["text", "browserslistrc"],
-    ".editorconfig" => &["text", "editorconfig"],
+    ".editorconfig" => &["text", "editorconfig", "ini"],
+    ".env" => &["text", "dotenv"],
     ".mailmap" => &["text", "mailmap"],
     ".pdbrc" => &["text", "python", "pdbrc"],
     "BUILD" => &["text", "bazel"],
@@ -393,8 +444,11 @@ pub static NAME_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map!
     "WORKSPACE" => &["text", "bazel"],
     "WORKSPACE.bazel" => &["text", "bazel"],
     "copy.bara.sky" => &["text", "bazel"],
+    // Rust project files are tagged by format and role, never `rust`
     "Cargo.toml" => &["text", "toml", "cargo"],
-    "Cargo.lock" => &["text", "toml", "cargo-lock"],
+    "Cargo.lock" => &["text", "toml", "cargo", "cargo-lock"],
+    "rust-toolchain" => &["text", "rust-toolchain"],
+    "rust-toolchain.toml" => &["text", "toml", "rust-toolchain"],
     "composer.json" => &["text", "json"],
     "composer.lock" => &["text", "json"],
     "go.mod" => &["text", "go-mod"],
@@ -407,10 +461,13 @@ pub static NAME_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_map!
     "pom.xml" => &["pom", "text", "xml"],
     "yarn.lock" => &["text", "yaml"],
     "config.ru" => &["text", "ruby"],
-    "Gemfile" => &["text", "ruby"],
+    "Brewfile" => &["text", "ruby", "brewfile"],
+    "Gemfile" => &["text", "ruby", "gemfile"],
     "Gemfile.lock" => &["text"],
-    "Rakefile" => &["text", "ruby"],
-    "Vagrantfile" => &["text", "ruby"],
+    "Guardfile" => &["text", "ruby", "guardfile"],
+    "Podfile" => &["text", "ruby", "podfile"],
+    "Rakefile" => &["text", "ruby", "rakefile"],
+    "Vagrantfile" => &["text", "ruby", "vagrantfile"],
     "bblayers.conf" => &["text", "bitbake"],
     "bitbake.conf" => &["text", "bitbake"],
     "rebar.config" => &["text", "erlang"],
@@ -452,3 +509,127 @@ pub fn get_name_tags(name: &str) -> TagSet {
         .map(|&tags| tags_from_array(tags))
         .unwrap_or_default()
 }
+
+/// Get the extensions whose tags include `tag`, sorted alphabetically.
+///
+/// Covers both [`EXTENSION_TAGS`] and [`EXTENSIONS_NEED_BINARY_CHECK_TAGS`];
+/// exact filenames from [`NAME_TAGS`] are not included. The tables are
+/// scanned on each call.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::extensions::extensions_for_tag;
+///
+/// let extensions = extensions_for_tag("python");
+/// assert!(extensions.contains(&"py"));
+/// assert!(!extensions.contains(&"rs"));
+/// ```
+pub fn extensions_for_tag(tag: &str) -> Vec<&'static str> {
+    let mut extensions: Vec<&'static str> = EXTENSION_TAGS
+        .entries()
+        .chain(EXTENSIONS_NEED_BINARY_CHECK_TAGS.entries())
+        .filter(|(_, tags)| tags.contains(&tag))
+        .map(|(&ext, _)| ext)
+        .collect();
+    extensions.sort_unstable();
+    extensions
+}
+
+/// Get tags for filenames recognized by pattern rather than exact name.
+///
+/// Covers pip requirements and constraints files such as `requirements.txt`,
+/// `requirements-dev.txt`, `requirements_test.txt` and `dev-requirements.txt`
+/// (the name followed by `-`, `_` or `.`, or preceded by `-`), and versioned shared
+/// libraries such as `libc.so.6` whose real extension is followed by
+/// version numbers. Minified assets following the `.min.<ext>` convention,
+/// such as `app.min.js`, are tagged `minified`; the language tags still come
+/// from the extension.
+pub fn get_name_pattern_tags(name: &str) -> TagSet {
+    let txt_stem = name
+        .rsplit_once('.')
+        .filter(|(_, ext)| ext.eq_ignore_ascii_case("txt"))
+        .map(|(stem, _)| stem);
+    if let Some(stem) = txt_stem {
+        let is_requirements = ["requirements", "constraints"].iter().any(|kind| {
+            stem == *kind
+                || stem
+                    .strip_prefix(kind)
+                    .is_some_and(|rest| rest.starts_with(['-', '_', '.']))
+                || stem
+                    .strip_suffix(kind)
+                    .is_some_and(|rest| rest.ends_with('-'))
+        });
+        if is_requirements {
+            return tags_from_array(&["text", "requirements-txt"]);
+        }
+    }
+
+    if is_versioned_shared_library(name) {
+        return tags_from_array(&["binary", "shared-library"]);
+    }
+
+    let is_minified = name.rsplit_once('.').is_some_and(|(stem, ext)| {
+        let stem = stem.to_ascii_lowercase();
+        !ext.is_empty()
+            && stem
+                .strip_suffix(".min")
+                .is_some_and(|base| !base.is_empty())
+    });
+    if is_minified {
+        return tags_from_array(&["minified"]);
+    }
+
+    TagSet::new()
+}
+
+/// Check for a shared library extension followed by numeric version
+/// components, e.g. `libfoo.so.1.2.3` or `libfoo.dll.2`.
+fn is_versioned_shared_library(name: &str) -> bool {
+    let components: Vec<&str> = name.split('.').skip(1).collect();
+    let version_len = components
+        .iter()
+        .rev()
+        .take_while(|c| !c.is_empty() && c.bytes().all(|b| b.is_ascii_digit()))
+        .count();
+
+    version_len > 0
+        && components
+            .len()
+            .checked_sub(version_len + 1)
+            .map(|i| components[i].to_lowercase())
+            .is_some_and(|ext| matches!(ext.as_str(), "so" | "dylib" | "dll"))
+}
+
+/// Get tags for files recognized by where they live, not just their name.
+///
+/// Covers CI/CD configuration conventions: GitHub Actions workflows under
+/// `.github/workflows/`, `.gitlab-ci.yml`, `.circleci/config.yml` and
+/// `azure-pipelines.yml`. Works for both relative and absolute paths.
+pub fn get_path_tags(path: &Path) -> TagSet {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return TagSet::new();
+    };
+    let Some(stem) = name
+        .strip_suffix(".yml")
+        .or_else(|| name.strip_suffix(".yaml"))
+    else {
+        return TagSet::new();
+    };
+
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let parent_name = parent.file_name().and_then(|n| n.to_str());
+    let grandparent_name = parent
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str());
+
+    let tag = match (stem, parent_name, grandparent_name) {
+        (_, Some("workflows"), Some(".github")) => "github-workflow",
+        (".gitlab-ci", _, _) => "gitlab-ci",
+        ("config", Some(".circleci"), _) => "circleci",
+        ("azure-pipelines", _, _) => "azure-pipelines",
+        _ => return TagSet::new(),
+    };
+    tags_from_array(&["text", "yaml", tag])
+}
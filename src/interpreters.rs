@@ -8,10 +8,13 @@ static INTERPRETER_TAGS: phf::Map<&'static str, &'static [&'static str]> = phf_m
     "awk" => &["awk"],
     "bash" => &["shell", "bash"],
     "bats" => &["shell", "bash", "bats"],
+    "bun" => &["javascript"],
     "cbsd" => &["shell", "cbsd"],
     "csh" => &["shell", "csh"],
     "dash" => &["shell", "dash"],
+    "deno" => &["javascript"],
     "expect" => &["expect"],
+    "fish" => &["fish"],
     "ksh" => &["shell", "ksh"],
     "node" => &["javascript"],
     "nodejs" => &["javascript"],
@@ -35,3 +38,10 @@ pub fn get_interpreter_tags(interpreter: &str) -> TagSet {
         .map(|&tags| tags_from_array(tags))
         .unwrap_or_default()
 }
+
+/// Get the names of all recognized interpreters, sorted alphabetically.
+pub fn all_interpreter_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = INTERPRETER_TAGS.keys().copied().collect();
+    names.sort_unstable();
+    names
+}
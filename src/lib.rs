@@ -38,11 +38,15 @@
 //! assert!(tags.contains("python"));
 //! ```
 //!
+//! The [`prelude`] module re-exports these along with the other common
+//! functions, types and tag constants: `use file_identify::prelude::*;`.
+//!
 //! ## Tag System
 //!
 //! Files are identified using a set of standardized tags:
 //!
-//! - **Type tags**: `file`, `directory`, `symlink`, `socket`
+//! - **Type tags**: `file`, `directory`, `symlink`, `socket`, `fifo`, `block-device`,
+//!   `char-device`
 //! - **Mode tags**: `executable`, `non-executable`
 //! - **Encoding tags**: `text`, `binary`
 //! - **Language/format tags**: `python`, `javascript`, `json`, `xml`, etc.
@@ -53,16 +57,32 @@
 //! conditions are:
 //!
 //! - [`IdentifyError::PathNotFound`] - when the specified path doesn't exist
+//! - [`IdentifyError::IoErrorAt`] - for I/O errors while reading a specific file
 //! - [`IdentifyError::IoError`] - for other I/O related errors
+//! - [`IdentifyError::InvalidConfig`] - when a configuration file can't be parsed
 
-use std::collections::HashSet;
+use std::ffi::OsStr;
 use std::fmt;
 use std::fs;
 use std::io::{BufReader, Read};
 use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Emit a diagnostic event when the `tracing` feature is enabled; compiles to
+// nothing otherwise.
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
 
+#[cfg(feature = "config")]
+mod config;
 pub mod extensions;
 pub mod interpreters;
+pub mod magic;
+pub mod prelude;
 pub mod tags;
 
 /// A tuple-like immutable container for shebang components that matches Python's tuple behavior.
@@ -99,6 +119,24 @@ impl ShebangTuple {
         self.components.is_empty()
     }
 
+    /// Parse the text of a shebang line that follows `#!`.
+    ///
+    /// Applies the same rules as [`parse_shebang`]: `/usr/bin/env` and its
+    /// options are stripped, and lines with non-ASCII or control characters
+    /// yield an empty tuple.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::ShebangTuple;
+    ///
+    /// let components = ShebangTuple::parse_line("/usr/bin/env python3 -u");
+    /// assert_eq!(components.as_slice(), ["python3", "-u"]);
+    /// ```
+    pub fn parse_line(line: &str) -> Self {
+        parse_shebang_command(line).components
+    }
+
     /// Get an element by index (equivalent to Python's `tuple[index]`)
     /// Returns None if index is out of bounds
     pub fn get(&self, index: usize) -> Option<&str> {
@@ -110,6 +148,21 @@ impl ShebangTuple {
         self.get(0)
     }
 
+    /// Get the interpreter (the first component), if any
+    pub fn interpreter(&self) -> Option<&str> {
+        self.first()
+    }
+
+    /// Get the arguments following the interpreter (empty if there are none)
+    pub fn args(&self) -> &[String] {
+        self.components.get(1..).unwrap_or_default()
+    }
+
+    /// Join the components with a separator (equivalent to Python's `sep.join(tuple)`)
+    pub fn join(&self, sep: &str) -> String {
+        self.components.join(sep)
+    }
+
     /// Convert to a Vec for internal use (consumes the tuple)
     pub fn into_vec(self) -> Vec<String> {
         self.components.into_vec()
@@ -145,6 +198,15 @@ impl<'a> IntoIterator for &'a ShebangTuple {
     }
 }
 
+impl IntoIterator for ShebangTuple {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.components.into_vec().into_iter()
+    }
+}
+
 // Implement FromIterator for collecting
 impl FromIterator<String> for ShebangTuple {
     fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
@@ -183,10 +245,71 @@ impl Default for ShebangTuple {
     }
 }
 
-use extensions::{get_extension_tags, get_extensions_need_binary_check_tags, get_name_tags};
+use extensions::{
+    get_extension_tags, get_extensions_need_binary_check_tags, get_name_pattern_tags,
+    get_name_tags, get_path_tags,
+};
 use interpreters::get_interpreter_tags;
+use magic::get_magic_tags;
 use tags::*;
 
+/// Policy for deciding whether a regular file is executable.
+///
+/// Some filesystems, such as FAT or Windows shares mounted on Linux, report
+/// every file as mode `0o777`. A policy other than the default lets callers
+/// work around such broken permission reporting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ExecutablePolicy {
+    /// Use the file's permission bits (`PATHEXT` on non-Unix platforms).
+    #[default]
+    PermissionBits,
+    /// Treat a file as executable only if it starts with a valid shebang.
+    ///
+    /// Files that are not read, because they exceed
+    /// [`max_content_bytes`](FileIdentifier::max_content_bytes) or because
+    /// shebang or content analysis is skipped, are treated as non-executable.
+    ShebangPresence,
+    /// Treat every file as non-executable.
+    Never,
+}
+
+/// Policy for handling errors while reading a file's content.
+///
+/// Set with [`FileIdentifier::on_content_error`]. Batch scans over flaky
+/// storage can use a fallback to keep identifying a file by name and
+/// metadata when its content cannot be read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ContentErrorPolicy {
+    /// Return the error.
+    #[default]
+    Propagate,
+    /// Tag the file `binary` if its encoding was not already determined.
+    AssumeBinary,
+    /// Tag the file `text` if its encoding was not already determined.
+    AssumeText,
+    /// Return the tags found so far, without adding an encoding tag.
+    Omit,
+}
+
+/// Verdict on whether a prefix of a stream is text, from [`classify_text_partial`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextVerdict {
+    /// A non-text byte was seen, so the content is binary whatever follows.
+    DefinitelyBinary,
+    /// Every byte so far is text; later bytes may still make it binary.
+    LikelyText,
+}
+
 /// Configuration for file identification behavior.
 ///
 /// Allows customizing which analysis steps to perform and their order.
@@ -195,7 +318,33 @@ use tags::*;
 pub struct FileIdentifier {
     skip_content_analysis: bool,
     skip_shebang_analysis: bool,
+    skip_permission_analysis: bool,
     custom_extensions: Option<std::collections::HashMap<String, TagSet>>,
+    custom_interpreters: Option<std::collections::HashMap<String, TagSet>>,
+    filename_patterns: Vec<(glob::Pattern, TagSet)>,
+    magic_signatures: Vec<(Vec<u8>, TagSet)>,
+    max_content_bytes: Option<u64>,
+    content_sniff_bytes: usize,
+    always_read_content: bool,
+    flag_conflicts: bool,
+    detect_script: bool,
+    detect_modelines: bool,
+    validate_utf8: bool,
+    treat_blank_as_empty: bool,
+    fast_language_only: bool,
+    tag_unknown: bool,
+    tag_hidden: bool,
+    treat_special_as_file: bool,
+    tag_unknown_shebang_as_script: bool,
+    resolve_polyglot_shebangs: bool,
+    resolve_node_module_type: bool,
+    executable_policy: ExecutablePolicy,
+    content_error_policy: ContentErrorPolicy,
+    max_symlink_depth: Option<usize>,
+    #[cfg(feature = "flate2")]
+    peek_inside_archives: bool,
+    #[cfg(feature = "zip")]
+    inspect_zip: bool,
 }
 
 impl Default for FileIdentifier {
@@ -216,7 +365,33 @@ impl FileIdentifier {
         Self {
             skip_content_analysis: false,
             skip_shebang_analysis: false,
+            skip_permission_analysis: false,
             custom_extensions: None,
+            custom_interpreters: None,
+            filename_patterns: Vec::new(),
+            magic_signatures: Vec::new(),
+            max_content_bytes: None,
+            content_sniff_bytes: CONTENT_SAMPLE_SIZE,
+            always_read_content: false,
+            flag_conflicts: false,
+            detect_script: false,
+            detect_modelines: false,
+            validate_utf8: false,
+            treat_blank_as_empty: false,
+            fast_language_only: false,
+            tag_unknown: false,
+            tag_hidden: false,
+            treat_special_as_file: false,
+            tag_unknown_shebang_as_script: false,
+            resolve_polyglot_shebangs: false,
+            resolve_node_module_type: false,
+            executable_policy: ExecutablePolicy::PermissionBits,
+            content_error_policy: ContentErrorPolicy::Propagate,
+            max_symlink_depth: None,
+            #[cfg(feature = "flate2")]
+            peek_inside_archives: false,
+            #[cfg(feature = "zip")]
+            inspect_zip: false,
         }
     }
 
@@ -238,30 +413,753 @@ impl FileIdentifier {
         self
     }
 
+    /// Skip the executable analysis.
+    ///
+    /// Regular files get neither an `executable` nor a `non-executable` tag,
+    /// and since shebangs are only parsed for executable files, shebang
+    /// analysis is skipped as well. Useful for content-only classification,
+    /// such as of files extracted from an archive. The executability passed
+    /// to [`identify_parts`](Self::identify_parts) is ignored too.
+    pub fn skip_permission_analysis(mut self) -> Self {
+        self.skip_permission_analysis = true;
+        self
+    }
+
+    /// Limit the size of files whose content is analyzed.
+    ///
+    /// Files larger than `limit` bytes are tagged `binary` without being
+    /// opened, and their shebang is not parsed, guarding against expensive
+    /// reads of huge or untrusted inputs.
+    /// By default there is no limit.
+    ///
+    /// The limit is checked against the length reported by file metadata.
+    /// Files reporting a length of zero, like those under `/proc` and `/sys`,
+    /// are always read; at most the
+    /// [`content_sniff_bytes`](Self::content_sniff_bytes) window is sampled
+    /// regardless.
+    pub fn max_content_bytes(mut self, limit: u64) -> Self {
+        self.max_content_bytes = Some(limit);
+        self
+    }
+
+    /// Set how many leading bytes are sampled for content analysis.
+    ///
+    /// Defaults to 1KB. A larger window catches binary content that starts
+    /// later in a file, at the cost of reading more; a smaller one is faster
+    /// but easier to fool. The window is at least one byte. It only bounds
+    /// how much is read: [`max_content_bytes`](Self::max_content_bytes)
+    /// still decides whether a file's content is read at all.
+    pub fn content_sniff_bytes(mut self, bytes: usize) -> Self {
+        self.content_sniff_bytes = bytes.max(1);
+        self
+    }
+
+    /// Always read content, ignoring the file length reported by metadata.
+    ///
+    /// Pseudo-filesystems such as `/proc` and `/sys` report lengths that do
+    /// not reflect their readable content. When set, the metadata length is
+    /// never used to skip content analysis, so
+    /// [`max_content_bytes`](Self::max_content_bytes) has no effect.
+    pub fn always_read_content(mut self) -> Self {
+        self.always_read_content = true;
+        self
+    }
+
+    /// Flag files whose content contradicts their filename-derived encoding.
+    ///
+    /// When the filename implies `text` but the content is binary (or vice
+    /// versa), a `content-mismatch` tag is added. This reads the content of
+    /// every non-empty file even when the filename already determines its
+    /// encoding. Has no effect when content analysis is skipped or the file
+    /// exceeds [`max_content_bytes`](Self::max_content_bytes).
+    pub fn flag_conflicts(mut self) -> Self {
+        self.flag_conflicts = true;
+        self
+    }
+
+    /// Tag text files with the dominant writing script of their content.
+    ///
+    /// Samples the [`content_sniff_bytes`](Self::content_sniff_bytes) window
+    /// of `text` files and adds one of `latin`, `cjk`, `cyrillic`, `arabic`
+    /// or `mixed-script` based on the Unicode ranges of the majority of
+    /// letters. This is best-effort metadata: binary files,
+    /// files that are not valid UTF-8 and files without letters get no tag.
+    pub fn detect_script(mut self) -> Self {
+        self.detect_script = true;
+        self
+    }
+
+    /// Tag text files with the language declared in an editor modeline.
+    ///
+    /// Scans the first and last five lines of `text` files for a Vim
+    /// modeline (`vim: set ft=ruby :`) or an Emacs mode line
+    /// (`-*- mode: ruby -*-`) and adds the tags of the declared language when
+    /// it is recognized as an interpreter, extension or language name. This
+    /// is best-effort: only the content sample is scanned, so the last lines
+    /// are only seen for files that fit in it.
+    pub fn detect_modelines(mut self) -> Self {
+        self.detect_modelines = true;
+        self
+    }
+
+    /// Tag text files with whether their content is valid UTF-8.
+    ///
+    /// The `text` tag permits arbitrary high bytes, so a Latin-1 file is
+    /// `text` without being UTF-8. When set, the
+    /// [`content_sniff_bytes`](Self::content_sniff_bytes) window of `text`
+    /// files is decoded and `valid-utf8` or `invalid-utf8` is added. A
+    /// multi-byte character cut off by the end of the window does not count
+    /// as invalid.
+    pub fn validate_utf8(mut self) -> Self {
+        self.validate_utf8 = true;
+        self
+    }
+
+    /// Tag effectively empty text files as `blank`.
+    ///
+    /// When set, `text` files whose content is only a UTF-8 byte order mark
+    /// and/or ASCII whitespace, including zero-length files, get a `blank`
+    /// tag so tools can skip them. Only files that fit in the
+    /// [`content_sniff_bytes`](Self::content_sniff_bytes) window are
+    /// considered, since the rest of a larger file is not read.
+    pub fn treat_blank_as_empty(mut self) -> Self {
+        self.treat_blank_as_empty = true;
+        self
+    }
+
+    /// Stop as soon as the filename identifies a format.
+    ///
+    /// For a fast "which language is this" answer: when filename analysis
+    /// yields any format tag, shebang and content analysis are skipped and
+    /// the result is just `file`, the mode tag and the format tags, with no
+    /// `text` or `binary` tag. Files the filename does not identify go
+    /// through the full pipeline. The result is always a subset of what full
+    /// identification returns.
+    pub fn fast_language_only(mut self) -> Self {
+        self.fast_language_only = true;
+        self
+    }
+
+    /// Tag regular files that no analysis step could identify as `unknown`.
+    ///
+    /// By default a file with no recognized name, shebang or content format
+    /// gets only its type, mode and encoding tags. When set, such files also
+    /// get an `unknown` tag, so callers need not special-case the absence of
+    /// a format tag. See [`tags_from_filename_or_unknown`] for the
+    /// filename-only equivalent.
+    pub fn tag_unknown(mut self) -> Self {
+        self.tag_unknown = true;
+        self
+    }
+
+    /// Tag hidden files as `hidden`.
+    ///
+    /// A file is hidden if its name starts with `.`, or on Windows if it has
+    /// the hidden attribute. The tag is added to every file type, including
+    /// directories, and does not change format detection.
+    /// [`identify_parts`](Self::identify_parts) only checks the name.
+    pub fn tag_hidden(mut self) -> Self {
+        self.tag_hidden = true;
+        self
+    }
+
+    /// Identify sockets and FIFOs as regular files.
+    ///
+    /// By default sockets and FIFOs are tagged `socket` or `fifo` without
+    /// further analysis. When set, they continue through filename and content
+    /// analysis like regular files. Directories and symlinks are unaffected.
+    ///
+    /// Note that reading a FIFO blocks until a writer opens it; combine with
+    /// [`skip_content_analysis`](Self::skip_content_analysis) if that is a concern.
+    pub fn treat_special_as_file(mut self) -> Self {
+        self.treat_special_as_file = true;
+        self
+    }
+
+    /// Tag executables with an unrecognized shebang interpreter as `script`.
+    ///
+    /// By default a file like `#!/opt/weird/tool` whose interpreter is unknown
+    /// gets no format tag. When set, such files are tagged `script` so no
+    /// shebang script is left entirely untagged.
+    pub fn tag_unknown_shebang_as_script(mut self) -> Self {
+        self.tag_unknown_shebang_as_script = true;
+        self
+    }
+
+    /// Also tag the interpreter a polyglot launcher shebang hands off to.
+    ///
+    /// Shebangs such as `#!/usr/bin/env bash -c 'exec python3 "$0" "$@"'`
+    /// run a shell command that re-executes the script with another
+    /// interpreter. When set, a `-c` argument whose command contains
+    /// `exec <interpreter>` adds that interpreter's tags alongside those of
+    /// the launching one. This is best-effort: only the shebang line is
+    /// inspected, and unrecognized interpreters are ignored.
+    pub fn resolve_polyglot_shebangs(mut self) -> Self {
+        self.resolve_polyglot_shebangs = true;
+        self
+    }
+
+    /// Tag `.js` files as `module` or `commonjs` from the nearest `package.json`.
+    ///
+    /// Node decides whether a `.js` file is an ES module from the `"type"`
+    /// field of the closest `package.json` in its directory or above:
+    /// `"module"` adds `module`, while `"commonjs"` or no field adds
+    /// `commonjs`. Files without a readable `package.json` above them get
+    /// neither tag. Relative paths are not searched above the current
+    /// directory. `.mjs` and `.cjs` files are always tagged by extension.
+    ///
+    /// Only applies to [`identify`](Self::identify), since it needs the
+    /// file's location.
+    pub fn resolve_node_module_type(mut self) -> Self {
+        self.resolve_node_module_type = true;
+        self
+    }
+
+    /// Set how [`identify`](Self::identify) decides whether a file is executable.
+    ///
+    /// Defaults to [`ExecutablePolicy::PermissionBits`]. Does not affect
+    /// [`identify_parts`](Self::identify_parts), where executability is
+    /// passed explicitly.
+    pub fn executable_policy(mut self, policy: ExecutablePolicy) -> Self {
+        self.executable_policy = policy;
+        self
+    }
+
+    /// Set what happens when reading a file's content fails.
+    ///
+    /// Defaults to [`ContentErrorPolicy::Propagate`]. With any other policy,
+    /// a failed content read ends content analysis for that file and the
+    /// tags found so far are returned, with the policy's fallback encoding.
+    /// Errors reading file metadata are always returned.
+    pub fn on_content_error(mut self, policy: ContentErrorPolicy) -> Self {
+        self.content_error_policy = policy;
+        self
+    }
+
+    /// Identify the content of gzip-compressed files as well.
+    ///
+    /// For single-member gzip files (not `.tar.gz` or `.tgz`), decompressed
+    /// content up to the [`content_sniff_bytes`](Self::content_sniff_bytes)
+    /// window is run through magic-byte, shebang and inner filename detection
+    /// (`access.log.gz` is analyzed as `access.log`), and the resulting
+    /// format tags are added alongside `gzip`. The file itself
+    /// stays `binary`; the inner content's `text` or `binary` tag is not
+    /// added.
+    ///
+    /// Requires the `flate2` feature.
+    #[cfg(feature = "flate2")]
+    pub fn peek_inside_archives(mut self) -> Self {
+        self.peek_inside_archives = true;
+        self
+    }
+
+    /// Identify the kind of container for ZIP-based files.
+    ///
+    /// For files tagged `zip`, the archive's central directory is read
+    /// (no entries are decompressed) and marker entries add a specific tag:
+    /// - `AndroidManifest.xml` adds `apk`
+    /// - `[Content_Types].xml` adds `ooxml` (`.docx`, `.xlsx`, `.pptx`)
+    /// - `META-INF/MANIFEST.MF` adds `jar`
+    ///
+    /// Only applies to [`identify`](Self::identify), since the central
+    /// directory sits at the end of the file. Files over the
+    /// [`max_content_bytes`](Self::max_content_bytes) limit are not inspected.
+    ///
+    /// Requires the `zip` feature.
+    #[cfg(feature = "zip")]
+    pub fn inspect_zip(mut self) -> Self {
+        self.inspect_zip = true;
+        self
+    }
+
+    /// Detect symlink cycles by resolving up to `depth` links of each chain.
+    ///
+    /// Symlinks are tagged `symlink` without being followed to their target.
+    /// With this option, a chain that does not end within `depth` links is
+    /// additionally tagged `symlink-loop`, so adversarial trees can neither
+    /// hang identification nor make it fail. Off by default, matching
+    /// [`tags_from_path`]; 40 matches typical OS limits.
+    pub fn max_symlink_depth(mut self, depth: usize) -> Self {
+        self.max_symlink_depth = Some(depth);
+        self
+    }
+
     /// Add custom file extension mappings.
     ///
     /// These will be checked before the built-in extension mappings.
     /// Useful for organization-specific or project-specific file types.
+    ///
+    /// Mapping an extension to an empty `TagSet` suppresses the built-in
+    /// mapping for it: the extension resolves to no format tags and neither
+    /// the built-in tables nor shebang analysis are consulted.
+    ///
+    /// Extensions match case-insensitively, like the built-in ones, so keys
+    /// are lowercased: `"MyExt"` matches `file.myext` and `file.MYEXT`.
     pub fn with_custom_extensions(
         mut self,
         extensions: std::collections::HashMap<String, TagSet>,
     ) -> Self {
+        let extensions = extensions
+            .into_iter()
+            .map(|(ext, tags)| (ext.to_lowercase(), tags))
+            .collect();
         self.custom_extensions = Some(extensions);
         self
     }
 
+    /// Add custom filename pattern rules.
+    ///
+    /// Each glob pattern is matched against the filename component only (not
+    /// the full path), and the tags of the first matching pattern are used.
+    /// Patterns are checked before custom extensions and the built-in
+    /// mappings, so `*.config.js` can take precedence over `.js`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::FileIdentifier;
+    /// use std::collections::HashSet;
+    ///
+    /// let identifier = FileIdentifier::new().with_filename_patterns(vec![(
+    ///     glob::Pattern::new("test_*.py").unwrap(),
+    ///     HashSet::from(["text", "python", "test"]),
+    /// )]);
+    /// let tags = identifier
+    ///     .identify_parts("test_parser.py", false, std::io::empty())
+    ///     .unwrap();
+    /// assert!(tags.contains("test"));
+    /// ```
+    pub fn with_filename_patterns(mut self, patterns: Vec<(glob::Pattern, TagSet)>) -> Self {
+        self.filename_patterns = patterns;
+        self
+    }
+
+    /// Add custom magic signatures for content-based detection.
+    ///
+    /// Each entry is a byte prefix and the tags to add when a file's content
+    /// starts with it. Longer prefixes take precedence; among prefixes of the
+    /// same length, the earlier entry wins. Signatures are checked during
+    /// content analysis, even for files whose name is recognized, and a
+    /// matching signature that carries `text` or `binary` replaces the
+    /// filename-derived encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::FileIdentifier;
+    /// use std::collections::HashSet;
+    ///
+    /// let identifier = FileIdentifier::new().with_magic_signatures(vec![(
+    ///     b"ACME".to_vec(),
+    ///     HashSet::from(["binary", "acme-archive"]),
+    /// )]);
+    /// let tags = identifier
+    ///     .identify_parts("data", false, &b"ACME\x00\x01"[..])
+    ///     .unwrap();
+    /// assert!(tags.contains("acme-archive"));
+    /// ```
+    pub fn with_magic_signatures(mut self, mut signatures: Vec<(Vec<u8>, TagSet)>) -> Self {
+        signatures.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self.magic_signatures = signatures;
+        self
+    }
+
+    /// Add custom shebang interpreter mappings.
+    ///
+    /// Keys are interpreter names (the basename of the shebang command, so
+    /// `/opt/bin/mytool` matches `mytool`). These are checked before the
+    /// built-in interpreter mappings.
+    pub fn with_custom_interpreters(
+        mut self,
+        interpreters: std::collections::HashMap<String, TagSet>,
+    ) -> Self {
+        self.custom_interpreters = Some(interpreters);
+        self
+    }
+
+    /// Merge another configuration into this one.
+    ///
+    /// Useful for layering per-project overrides on top of a base policy:
+    /// - Boolean options are OR-ed, so a step skipped or a feature enabled by
+    ///   either configuration stays skipped or enabled.
+    /// - Custom extension and interpreter maps are unioned; `other` wins on
+    ///   conflicting keys.
+    /// - Filename patterns and magic signatures are combined, with `other`'s
+    ///   checked first.
+    /// - `other`'s content size limit and symlink depth win when set.
+    /// - For the sniff size, executable policy and content error policy,
+    ///   `other`'s value wins when it differs from the default.
+    pub fn merge(mut self, other: FileIdentifier) -> Self {
+        let defaults = FileIdentifier::new();
+        let FileIdentifier {
+            skip_content_analysis,
+            skip_shebang_analysis,
+            skip_permission_analysis,
+            custom_extensions,
+            custom_interpreters,
+            mut filename_patterns,
+            mut magic_signatures,
+            max_content_bytes,
+            content_sniff_bytes,
+            always_read_content,
+            flag_conflicts,
+            detect_script,
+            detect_modelines,
+            validate_utf8,
+            treat_blank_as_empty,
+            fast_language_only,
+            tag_unknown,
+            tag_hidden,
+            treat_special_as_file,
+            tag_unknown_shebang_as_script,
+            resolve_polyglot_shebangs,
+            resolve_node_module_type,
+            executable_policy,
+            content_error_policy,
+            max_symlink_depth,
+            #[cfg(feature = "flate2")]
+            peek_inside_archives,
+            #[cfg(feature = "zip")]
+            inspect_zip,
+        } = other;
+
+        self.skip_content_analysis |= skip_content_analysis;
+        self.skip_shebang_analysis |= skip_shebang_analysis;
+        self.skip_permission_analysis |= skip_permission_analysis;
+        self.always_read_content |= always_read_content;
+        self.flag_conflicts |= flag_conflicts;
+        self.detect_script |= detect_script;
+        self.detect_modelines |= detect_modelines;
+        self.validate_utf8 |= validate_utf8;
+        self.treat_blank_as_empty |= treat_blank_as_empty;
+        self.fast_language_only |= fast_language_only;
+        self.tag_unknown |= tag_unknown;
+        self.tag_hidden |= tag_hidden;
+        self.treat_special_as_file |= treat_special_as_file;
+        self.tag_unknown_shebang_as_script |= tag_unknown_shebang_as_script;
+        self.resolve_polyglot_shebangs |= resolve_polyglot_shebangs;
+        self.resolve_node_module_type |= resolve_node_module_type;
+        #[cfg(feature = "flate2")]
+        {
+            self.peek_inside_archives |= peek_inside_archives;
+        }
+        #[cfg(feature = "zip")]
+        {
+            self.inspect_zip |= inspect_zip;
+        }
+
+        self.max_content_bytes = max_content_bytes.or(self.max_content_bytes);
+        self.max_symlink_depth = max_symlink_depth.or(self.max_symlink_depth);
+        if content_sniff_bytes != defaults.content_sniff_bytes {
+            self.content_sniff_bytes = content_sniff_bytes;
+        }
+        if executable_policy != defaults.executable_policy {
+            self.executable_policy = executable_policy;
+        }
+        if content_error_policy != defaults.content_error_policy {
+            self.content_error_policy = content_error_policy;
+        }
+
+        self.custom_extensions = match (self.custom_extensions, custom_extensions) {
+            (Some(mut base), Some(overrides)) => {
+                base.extend(overrides);
+                Some(base)
+            }
+            (base, overrides) => overrides.or(base),
+        };
+        self.custom_interpreters = match (self.custom_interpreters, custom_interpreters) {
+            (Some(mut base), Some(overrides)) => {
+                base.extend(overrides);
+                Some(base)
+            }
+            (base, overrides) => overrides.or(base),
+        };
+
+        filename_patterns.append(&mut self.filename_patterns);
+        self.filename_patterns = filename_patterns;
+
+        magic_signatures.append(&mut self.magic_signatures);
+        magic_signatures.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self.magic_signatures = magic_signatures;
+
+        self
+    }
+
     /// Identify a file using the configured settings.
     ///
     /// This is equivalent to `tags_from_path` but with customizable behavior.
     pub fn identify<P: AsRef<Path>>(&self, path: P) -> Result<TagSet> {
-        self.identify_with_config(path)
+        self.identify_with_config(path.as_ref())
+            .map(ExplainedTags::into_tags)
+    }
+
+    /// Identify a file and report why each tag was added.
+    ///
+    /// A diagnostic counterpart to [`identify`](Self::identify) that returns
+    /// the same tags, in the order they were added, each annotated with the
+    /// analysis stage that produced it. A tag produced by more than one stage
+    /// is reported once, with the first stage that added it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::{FileIdentifier, TagOrigin};
+    /// # use std::fs;
+    /// # use tempfile::tempdir;
+    ///
+    /// # let dir = tempdir().unwrap();
+    /// # let file_path = dir.path().join("x.py");
+    /// # fs::write(&file_path, "print('hello')").unwrap();
+    /// let explained = FileIdentifier::new().identify_explained(&file_path).unwrap();
+    /// assert!(explained.contains(&("python".to_string(), TagOrigin::Extension)));
+    /// assert!(explained.contains(&("file".to_string(), TagOrigin::FileType)));
+    /// ```
+    pub fn identify_explained<P: AsRef<Path>>(&self, path: P) -> Result<Vec<(String, TagOrigin)>> {
+        self.identify_with_config(path.as_ref())
+            .map(ExplainedTags::into_explained)
+    }
+
+    /// Identify a file and report the time spent in each analysis stage.
+    ///
+    /// Returns the same tags as [`identify`](Self::identify). This is a
+    /// diagnostic for profiling identification over large trees, to decide
+    /// which analyses are worth skipping.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::FileIdentifier;
+    /// # use std::fs;
+    /// # use tempfile::tempdir;
+    ///
+    /// # let dir = tempdir().unwrap();
+    /// # let file_path = dir.path().join("data");
+    /// # fs::write(&file_path, "some text").unwrap();
+    /// let (tags, timings) = FileIdentifier::new().identify_timed(&file_path).unwrap();
+    /// assert!(tags.contains("text"));
+    /// println!("content analysis took {:?}", timings.content);
+    /// ```
+    pub fn identify_timed<P: AsRef<Path>>(&self, path: P) -> Result<(TagSet, StageTimings)> {
+        let tags = self.identify_with_config(path.as_ref())?;
+        let timings = tags.timings;
+        Ok((tags.into_tags(), timings))
+    }
+
+    /// Identify a file and compute the SHA-256 hash of its content in one pass.
+    ///
+    /// The file is read once: the content sample used for identification is
+    /// also the start of the hashed stream, and the hash always covers the
+    /// entire file, even when [`max_content_bytes`](Self::max_content_bytes)
+    /// skips content analysis. Files that are not tagged `file`, such as
+    /// directories and symlinks, are not read and get the hash of empty
+    /// content.
+    ///
+    /// Requires the `sha2` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`identify`](Self::identify), and
+    /// [`IdentifyError::IoErrorAt`] if reading the file for hashing fails.
+    #[cfg(feature = "sha2")]
+    pub fn identify_with_hash<P: AsRef<Path>>(&self, path: P) -> Result<(TagSet, [u8; 32])> {
+        use sha2::{Digest, Sha256};
+
+        let path = path.as_ref();
+        let file = std::cell::OnceCell::new();
+        let mut sample = ContentSample::new(
+            || match file.get() {
+                Some(file) => Ok(file),
+                None => fs::File::open(path).map(|opened| file.get_or_init(|| opened)),
+            },
+            self.content_sniff_bytes,
+        );
+        let tags = self.identify_path_with_sample(path, &mut sample)?;
+
+        let mut hasher = Sha256::new();
+        if tags.contains(FILE) {
+            let hashed = sample.bytes().and_then(|head| {
+                hasher.update(head);
+                // Reading the sample left the file positioned just past it
+                let mut rest = file.get().expect("content sample opened the file");
+                std::io::copy(&mut rest, &mut hasher)
+            });
+            hashed.map_err(|e| IdentifyError::from(e).at_path(path))?;
+        }
+
+        Ok((tags.into_tags(), hasher.finalize().into()))
     }
 
-    fn identify_with_config<P: AsRef<Path>>(&self, path: P) -> Result<TagSet> {
+    /// Identify a file and return the interpreter named by its shebang.
+    ///
+    /// The interpreter is the basename of the shebang command (`python3` for
+    /// both `#!/usr/bin/python3` and `#!/usr/bin/env python3`), and is only
+    /// reported for executable regular files that start with a shebang. It is
+    /// `None` for other files, when the shebang cannot be read, and when
+    /// [`skip_shebang_analysis`](Self::skip_shebang_analysis) is set. The
+    /// file's leading bytes are read at most once for both results.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`identify`](Self::identify).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::FileIdentifier;
+    /// # use std::fs;
+    /// # use std::os::unix::fs::PermissionsExt;
+    /// # use tempfile::tempdir;
+    ///
+    /// # let dir = tempdir().unwrap();
+    /// # let script = dir.path().join("run");
+    /// # fs::write(&script, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+    /// # fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    /// let (tags, interpreter) = FileIdentifier::new().identify_script(&script).unwrap();
+    /// assert!(tags.contains("python"));
+    /// assert_eq!(interpreter.as_deref(), Some("python3"));
+    /// ```
+    pub fn identify_script<P: AsRef<Path>>(&self, path: P) -> Result<(TagSet, Option<String>)> {
         let path = path.as_ref();
+        let mut sample = ContentSample::new(|| fs::File::open(path), self.content_sniff_bytes);
+        let tags = self.identify_path_with_sample(path, &mut sample)?;
+
+        let interpreter =
+            if tags.contains(FILE) && tags.contains(EXECUTABLE) && !self.skip_shebang_analysis {
+                sample
+                    .bytes()
+                    .map_err(IdentifyError::from)
+                    .and_then(parse_shebang)
+                    .ok()
+                    .and_then(|shebang| {
+                        let interpreter = shebang.interpreter()?;
+                        Some(
+                            interpreter
+                                .rsplit('/')
+                                .next()
+                                .unwrap_or(interpreter)
+                                .to_string(),
+                        )
+                    })
+            } else {
+                None
+            };
+
+        Ok((tags.into_tags(), interpreter))
+    }
+
+    /// Identify a regular file from its parts, without touching the filesystem.
+    ///
+    /// Takes the filename, an explicit executability flag and a reader over
+    /// the file's content, and runs the same filename, shebang and content
+    /// analysis as [`identify`](Self::identify). This is useful for callers
+    /// that compute executability themselves, and makes identification
+    /// testable on platforms without Unix permission bits.
+    ///
+    /// The reader is only consulted if shebang or content analysis needs it,
+    /// and at most the [`content_sniff_bytes`](Self::content_sniff_bytes)
+    /// window is read from it. The content size limit set by
+    /// [`max_content_bytes`](Self::max_content_bytes) does not apply, since
+    /// the content length is unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdentifyError::IoError`] if reading the content fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::FileIdentifier;
+    /// use std::io::Cursor;
+    ///
+    /// let content = Cursor::new(b"#!/usr/bin/env python3\nprint('hello')");
+    /// let tags = FileIdentifier::new()
+    ///     .identify_parts("script", true, content)
+    ///     .unwrap();
+    /// assert!(tags.contains("executable"));
+    /// assert!(tags.contains("python"));
+    /// assert!(tags.contains("text"));
+    /// ```
+    pub fn identify_parts<R: Read>(
+        &self,
+        filename: &str,
+        is_executable: bool,
+        reader: R,
+    ) -> Result<TagSet> {
+        let filename = Path::new(filename).file_name();
+        let mut reader = Some(reader);
+        let mut sample = ContentSample::new(
+            move || {
+                reader
+                    .take()
+                    .ok_or_else(|| std::io::Error::other("content reader already consumed"))
+            },
+            self.content_sniff_bytes,
+        );
+        self.identify_regular_file(filename, is_executable, false, &mut sample)
+            .map(ExplainedTags::into_tags)
+    }
+
+    /// Identify many in-memory blobs, reusing one scratch buffer across items.
+    ///
+    /// Each item is a filename and the blob's content. Blobs are treated as
+    /// non-executable regular files, so each result matches
+    /// `identify_parts(&filename, false, content.as_slice())`. Results are
+    /// returned in input order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use file_identify::FileIdentifier;
+    ///
+    /// let results = FileIdentifier::new().identify_many(vec![
+    ///     ("main.rs".to_string(), b"fn main() {}".to_vec()),
+    ///     ("blob".to_string(), b"\x00\x01\x02".to_vec()),
+    /// ]);
+    /// assert!(results[0].as_ref().unwrap().contains("rust"));
+    /// assert!(results[1].as_ref().unwrap().contains("binary"));
+    /// ```
+    pub fn identify_many<I>(&self, items: I) -> Vec<Result<TagSet>>
+    where
+        I: IntoIterator<Item = (String, Vec<u8>)>,
+    {
+        let mut scratch = Vec::with_capacity(self.content_sniff_bytes);
+        items
+            .into_iter()
+            .map(|(filename, content)| {
+                let name = Path::new(&filename).file_name();
+                let mut sample = ContentSample::with_buffer(
+                    || Ok(content.as_slice()),
+                    std::mem::take(&mut scratch),
+                    self.content_sniff_bytes,
+                );
+                let result = self
+                    .identify_regular_file(name, false, false, &mut sample)
+                    .map(ExplainedTags::into_tags);
+                scratch = sample.into_buffer();
+                result
+            })
+            .collect()
+    }
+
+    fn identify_with_config(&self, path: &Path) -> Result<ExplainedTags> {
+        let mut sample = ContentSample::new(|| fs::File::open(path), self.content_sniff_bytes);
+        self.identify_path_with_sample(path, &mut sample)
+    }
+
+    /// Identify `path`, reading its content through `sample`.
+    fn identify_path_with_sample<F, R>(
+        &self,
+        path: &Path,
+        sample: &mut ContentSample<F>,
+    ) -> Result<ExplainedTags>
+    where
+        F: FnMut() -> std::io::Result<R>,
+        R: Read,
+    {
         let path_str = path.to_string_lossy();
 
         // Get file metadata
+        let started = Instant::now();
         let metadata = match fs::symlink_metadata(path) {
             Ok(meta) => meta,
             Err(_) => {
@@ -271,76 +1169,674 @@ impl FileIdentifier {
             }
         };
 
-        // Step 1: Check for non-regular file types (directory, symlink, socket)
-        if let Some(file_type_tags) = analyze_file_type(&metadata) {
-            return Ok(file_type_tags);
+        // Step 1: Check for non-regular file types (directory, symlink, socket, fifo, device)
+        if let Some(mut file_type_tags) = analyze_file_type(&metadata) {
+            if file_type_tags.contains(SYMLINK)
+                && self
+                    .max_symlink_depth
+                    .is_some_and(|depth| symlink_chain_exceeds(path, depth))
+            {
+                file_type_tags.insert(SYMLINK_LOOP);
+            }
+            let is_special = file_type_tags.contains(SOCKET) || file_type_tags.contains(FIFO);
+            if !(self.treat_special_as_file && is_special) {
+                trace_event!(debug, path = %path_str, tags = ?file_type_tags, "non-regular file");
+                let mut tags = ExplainedTags::default();
+                tags.timings.metadata = started.elapsed();
+                tags.extend(file_type_tags, TagOrigin::FileType);
+                if self.tag_hidden {
+                    if has_hidden_name(path.file_name()) {
+                        tags.insert(HIDDEN, TagOrigin::Name);
+                    } else if has_hidden_attribute(&metadata) {
+                        tags.insert(HIDDEN, TagOrigin::FileType);
+                    }
+                }
+                return Ok(tags);
+            }
+        }
+
+        // Step 2: Analyze permissions (executable vs non-executable)
+        let metadata_time = started.elapsed();
+        let started = Instant::now();
+        let exceeds_limit = !self.always_read_content
+            && self
+                .max_content_bytes
+                .is_some_and(|limit| metadata.len() > limit);
+        let is_executable = match self.executable_policy {
+            _ if self.skip_permission_analysis => false,
+            ExecutablePolicy::PermissionBits => analyze_permissions(path, &metadata),
+            ExecutablePolicy::ShebangPresence
+                if exceeds_limit || self.skip_shebang_analysis || self.skip_content_analysis =>
+            {
+                false
+            }
+            ExecutablePolicy::ShebangPresence => sample
+                .bytes()
+                .map_err(IdentifyError::from)
+                .and_then(parse_shebang)
+                .is_ok_and(|shebang| !shebang.is_empty()),
+            ExecutablePolicy::Never => false,
+        };
+        let permission_time = started.elapsed();
+        trace_event!(debug, path = %path_str, is_executable, "analyzed permissions");
+
+        // Steps 3-8: Analyze the regular file's name and content
+        let filename = path.file_name();
+        let mut tags = self
+            .identify_regular_file(filename, is_executable, exceeds_limit, sample)
+            .map_err(|e| e.at_path(path))?;
+        tags.timings.metadata = metadata_time;
+        tags.timings.permission = permission_time;
+
+        #[cfg(feature = "zip")]
+        if self.inspect_zip
+            && !self.skip_content_analysis
+            && !self.fast_language_only
+            && !exceeds_limit
+            && tags.contains("zip")
+        {
+            let started = Instant::now();
+            if let Some(container_tag) = zip_container_tag(path) {
+                tags.insert(container_tag, TagOrigin::Content);
+            }
+            tags.timings.content += started.elapsed();
+        }
+        let started = Instant::now();
+        if self.resolve_node_module_type
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("js"))
+        {
+            if let Some(module_type) = node_module_type(path) {
+                tags.insert(module_type, TagOrigin::Name);
+            }
+        }
+        let mut path_tags = get_path_tags(path);
+        if self.fast_language_only && !tags.tags.iter().any(is_encoding_tag) {
+            path_tags.retain(|tag| !is_encoding_tag(tag));
+        }
+        if !path_tags.is_empty() {
+            // A path convention identifies the file after all
+            tags.retain(|tag| tag != UNKNOWN);
+        }
+        if self.tag_hidden && has_hidden_attribute(&metadata) {
+            tags.insert(HIDDEN, TagOrigin::FileType);
         }
+        tags.extend(path_tags, TagOrigin::Name);
+        tags.timings.filename += started.elapsed();
+        Ok(tags)
+    }
 
-        // Step 2: This is a regular file - start building tag set
-        let mut tags = TagSet::new();
-        tags.insert(FILE);
+    /// Identify a regular file from its name, executability and content sample.
+    ///
+    /// Shared by [`identify`](Self::identify) and
+    /// [`identify_parts`](Self::identify_parts).
+    fn identify_regular_file<F, R>(
+        &self,
+        filename: Option<&OsStr>,
+        is_executable: bool,
+        exceeds_limit: bool,
+        sample: &mut ContentSample<F>,
+    ) -> Result<ExplainedTags>
+    where
+        F: FnMut() -> std::io::Result<R>,
+        R: Read,
+    {
+        // Step 3: This is a regular file - start building tag set
+        let mut tags = ExplainedTags::default();
+        tags.insert(FILE, TagOrigin::FileType);
+        let is_executable = is_executable && !self.skip_permission_analysis;
+        if !self.skip_permission_analysis {
+            let mode = if is_executable {
+                EXECUTABLE
+            } else {
+                NON_EXECUTABLE
+            };
+            tags.insert(mode, TagOrigin::Permission);
+        }
 
-        // Step 3: Analyze permissions (executable vs non-executable)
-        let is_executable = analyze_permissions(path, &metadata);
-        if is_executable {
-            tags.insert(EXECUTABLE);
-        } else {
-            tags.insert(NON_EXECUTABLE);
+        if self.tag_hidden && has_hidden_name(filename) {
+            tags.insert(HIDDEN, TagOrigin::Name);
         }
 
         // Step 4: Analyze filename and potentially shebang (with custom config)
-        let filename_and_shebang_tags =
-            self.analyze_filename_and_shebang_configured(path, is_executable);
-        tags.extend(filename_and_shebang_tags);
+        let started = Instant::now();
+        self.analyze_filename_and_shebang_configured(
+            filename,
+            is_executable,
+            exceeds_limit,
+            sample,
+            &mut tags,
+        );
+        // The shebang stage timed itself
+        tags.timings.filename = started.elapsed().saturating_sub(tags.timings.shebang);
+        trace_event!(debug, ?filename, tags = ?tags.tags, "analyzed filename");
+
+        if self.fast_language_only && tags.tags.iter().any(|tag| is_format_tag(tag)) {
+            tags.retain(|tag| !is_encoding_tag(tag));
+            trace_event!(debug, ?filename, tags = ?tags.tags, "format found, skipping content");
+            return Ok(tags);
+        }
 
-        // Step 5: Analyze content encoding (text vs binary) if not skipped and not already determined
+        // Steps 5-10: Analyze content unless skipped
         if !self.skip_content_analysis {
-            let encoding_tags = analyze_content_encoding(path, &tags)?;
-            tags.extend(encoding_tags);
+            let started = Instant::now();
+            let analyzed = self.analyze_content(filename, exceeds_limit, sample, &mut tags);
+            tags.timings.content = started.elapsed();
+            if let Err(e) = analyzed {
+                let fallback = match self.content_error_policy {
+                    ContentErrorPolicy::Propagate => return Err(e),
+                    ContentErrorPolicy::AssumeBinary => Some(BINARY),
+                    ContentErrorPolicy::AssumeText => Some(TEXT),
+                    ContentErrorPolicy::Omit => None,
+                };
+                trace_event!(debug, ?filename, error = %e, "content analysis failed");
+                if let Some(tag) = fallback {
+                    if !tags.tags.iter().any(is_encoding_tag) {
+                        tags.insert(tag, TagOrigin::Content);
+                    }
+                }
+            }
         }
 
+        if self.tag_unknown && !tags.tags.iter().any(|tag| is_format_tag(tag)) {
+            tags.insert(UNKNOWN, TagOrigin::Custom);
+        }
+
+        trace_event!(trace, ?filename, tags = ?tags.tags, "identified");
         Ok(tags)
     }
 
-    fn analyze_filename_and_shebang_configured<P: AsRef<Path>>(
+    /// Run the content analysis steps, adding their tags to `tags`.
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "flate2")),
+        allow(unused_variables)
+    )]
+    fn analyze_content<F, R>(
         &self,
-        path: P,
-        is_executable: bool,
-    ) -> TagSet {
-        let path = path.as_ref();
-        let mut tags = TagSet::new();
-
-        // Check filename-based tags first (including custom extensions)
-        if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-            // Check custom extensions first if provided
-            if let Some(custom_exts) = &self.custom_extensions {
-                if let Some(ext) = Path::new(filename).extension().and_then(|e| e.to_str()) {
-                    let ext_lower = ext.to_lowercase();
-                    if let Some(ext_tags) = custom_exts.get(&ext_lower) {
-                        tags.extend(ext_tags.iter().cloned());
-                        return tags; // Custom extension takes precedence
-                    }
+        filename: Option<&OsStr>,
+        exceeds_limit: bool,
+        sample: &mut ContentSample<F>,
+        tags: &mut ExplainedTags,
+    ) -> Result<()>
+    where
+        F: FnMut() -> std::io::Result<R>,
+        R: Read,
+    {
+        // Step 5: Analyze content encoding (text vs binary) if not already determined
+        // Custom magic signatures are content evidence and may override
+        // the filename-derived encoding
+        if !exceeds_limit && !self.magic_signatures.is_empty() {
+            let bytes = sample.bytes()?;
+            if let Some((_, magic_tags)) = self
+                .magic_signatures
+                .iter()
+                .find(|(prefix, _)| bytes.starts_with(prefix))
+            {
+                if magic_tags.iter().any(is_encoding_tag) {
+                    tags.retain(|tag| !is_encoding_tag(tag));
                 }
+                tags.extend(magic_tags.iter().copied(), TagOrigin::Custom);
             }
+        }
 
-            // Fall back to standard filename analysis
-            let filename_tags = tags_from_filename(filename);
-            if !filename_tags.is_empty() {
-                tags.extend(filename_tags);
-            } else if is_executable && !self.skip_shebang_analysis {
-                // Parse shebang for executable files without recognized extensions
-                if let Ok(shebang_components) = parse_shebang_from_file(path) {
-                    if !shebang_components.is_empty() {
-                        let interpreter_tags = tags_from_interpreter(&shebang_components[0]);
-                        tags.extend(interpreter_tags);
-                    }
-                }
+        let has_encoding = tags.tags.iter().any(|tag| ENCODING_TAGS.contains(tag));
+        if exceeds_limit {
+            if !has_encoding {
+                tags.insert(BINARY, TagOrigin::Content);
+            }
+        } else if !has_encoding {
+            tags.extend(
+                encoding_tags_from_sample(sample.bytes()?)?,
+                TagOrigin::Content,
+            );
+        } else if self.flag_conflicts {
+            let bytes = sample.bytes()?;
+            if !bytes.is_empty() && bytes_are_text(bytes) != tags.contains(TEXT) {
+                tags.insert(CONTENT_MISMATCH, TagOrigin::Content);
+            }
+        }
+        trace_event!(
+            debug,
+            ?filename,
+            exceeds_limit,
+            text = tags.contains(TEXT),
+            "analyzed encoding"
+        );
+
+        // Step 6: Optionally classify the dominant script of text content
+        if self.detect_script && !exceeds_limit && tags.contains(TEXT) {
+            if let Some(script_tag) = dominant_script_of_sample(sample.bytes()?) {
+                tags.insert(script_tag, TagOrigin::Content);
             }
         }
 
-        tags
-    }
-}
+        // Step 7: Optionally validate text content as UTF-8
+        if self.validate_utf8 && !exceeds_limit && tags.contains(TEXT) {
+            if is_valid_utf8_sample(sample.bytes()?, self.content_sniff_bytes) {
+                tags.insert(VALID_UTF8, TagOrigin::Content);
+            } else {
+                tags.insert(INVALID_UTF8, TagOrigin::Content);
+            }
+        }
+
+        // Step 8: Optionally read the language from editor modelines
+        if self.detect_modelines && !exceeds_limit && tags.contains(TEXT) {
+            let language_tags = modeline_language(sample.bytes()?)
+                .map(|language| language_tags_from_name(&language))
+                .unwrap_or_default();
+            tags.extend(language_tags, TagOrigin::Content);
+        }
+
+        // Step 9: Optionally tag text files with no content but whitespace
+        if self.treat_blank_as_empty && !exceeds_limit && tags.contains(TEXT) {
+            let bytes = sample.bytes()?;
+            if bytes.len() < self.content_sniff_bytes && is_blank(bytes) {
+                tags.insert(BLANK, TagOrigin::Content);
+            }
+        }
+
+        // Step 10: Optionally identify the content of gzip files
+        #[cfg(feature = "flate2")]
+        if self.peek_inside_archives && !exceeds_limit {
+            let bytes = sample.bytes()?;
+            if bytes.starts_with(b"\x1f\x8b") {
+                tags.insert("gzip", TagOrigin::Content);
+                let filename = filename.and_then(OsStr::to_str);
+                let inner_tags = gzip_inner_tags(filename, bytes, self.content_sniff_bytes);
+                tags.extend(inner_tags, TagOrigin::Content);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn analyze_filename_and_shebang_configured<F, R>(
+        &self,
+        filename: Option<&OsStr>,
+        is_executable: bool,
+        exceeds_limit: bool,
+        sample: &mut ContentSample<F>,
+        tags: &mut ExplainedTags,
+    ) where
+        F: FnMut() -> std::io::Result<R>,
+        R: Read,
+    {
+        // Check filename-based tags first (including custom extensions)
+        let Some(filename) = filename else {
+            return;
+        };
+
+        // Check custom filename patterns first; the first match wins
+        if let Some((_, pattern_tags)) = self
+            .filename_patterns
+            .iter()
+            .find(|(pattern, _)| pattern.matches(&filename.to_string_lossy()))
+        {
+            tags.extend(pattern_tags.iter().copied(), TagOrigin::Custom);
+            return;
+        }
+
+        // Check custom extensions if provided
+        if let Some(custom_exts) = &self.custom_extensions {
+            if let Some(ext) = Path::new(filename).extension().and_then(|e| e.to_str()) {
+                let ext_lower = ext.to_lowercase();
+                if let Some(ext_tags) = custom_exts.get(&ext_lower) {
+                    // Custom extension takes precedence; an empty mapping
+                    // explicitly clears the built-in tags
+                    tags.extend(ext_tags.iter().copied(), TagOrigin::Custom);
+                    return;
+                }
+            }
+        }
+
+        // Fall back to standard filename analysis
+        let (name_tags, ext_tags) = os_filename_tags_by_origin(filename);
+        if !name_tags.is_empty() || !ext_tags.is_empty() {
+            tags.extend(name_tags, TagOrigin::Name);
+            tags.extend(ext_tags, TagOrigin::Extension);
+        } else if is_executable && !self.skip_shebang_analysis && !exceeds_limit {
+            // Parse shebang for executable files without recognized extensions
+            let started = Instant::now();
+            self.analyze_shebang(sample, tags);
+            tags.timings.shebang = started.elapsed();
+        }
+    }
+
+    /// Add the tags of an executable's shebang interpreter to `tags`.
+    fn analyze_shebang<F, R>(&self, sample: &mut ContentSample<F>, tags: &mut ExplainedTags)
+    where
+        F: FnMut() -> std::io::Result<R>,
+        R: Read,
+    {
+        let shebang = sample
+            .bytes()
+            .map_err(IdentifyError::from)
+            .and_then(parse_shebang);
+        let Ok(shebang_components) = shebang else {
+            return;
+        };
+        trace_event!(trace, components = %shebang_components, "parsed shebang");
+        let Some(interpreter) = shebang_components.interpreter() else {
+            return;
+        };
+
+        let interpreter_name = interpreter.rsplit('/').next().unwrap_or(interpreter);
+        if let Some(custom_tags) = self
+            .custom_interpreters
+            .as_ref()
+            .and_then(|custom| custom.get(interpreter_name))
+        {
+            tags.extend(custom_tags.iter().copied(), TagOrigin::Custom);
+            return;
+        }
+
+        let interpreter_tags = tags_from_interpreter(interpreter);
+        if interpreter_tags.is_empty() && self.tag_unknown_shebang_as_script {
+            tags.insert(SCRIPT, TagOrigin::Shebang);
+        }
+        tags.extend(interpreter_tags, TagOrigin::Shebang);
+
+        if self.resolve_polyglot_shebangs {
+            if let Some(target) = polyglot_exec_target(shebang_components.args()) {
+                tags.extend(tags_from_interpreter(target), TagOrigin::Shebang);
+            }
+        }
+    }
+}
+
+// In-place counterparts of the consuming builder methods, for configuring an
+// identifier conditionally or reusing one across runs.
+impl FileIdentifier {
+    /// Restore every setting to its default, as in [`new`](Self::new).
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Enable or disable [`skip_content_analysis`](Self::skip_content_analysis).
+    pub fn set_skip_content_analysis(&mut self, enabled: bool) -> &mut Self {
+        self.skip_content_analysis = enabled;
+        self
+    }
+
+    /// Enable or disable [`skip_shebang_analysis`](Self::skip_shebang_analysis).
+    pub fn set_skip_shebang_analysis(&mut self, enabled: bool) -> &mut Self {
+        self.skip_shebang_analysis = enabled;
+        self
+    }
+
+    /// Enable or disable [`skip_permission_analysis`](Self::skip_permission_analysis).
+    pub fn set_skip_permission_analysis(&mut self, enabled: bool) -> &mut Self {
+        self.skip_permission_analysis = enabled;
+        self
+    }
+
+    /// Enable or disable [`always_read_content`](Self::always_read_content).
+    pub fn set_always_read_content(&mut self, enabled: bool) -> &mut Self {
+        self.always_read_content = enabled;
+        self
+    }
+
+    /// Enable or disable [`flag_conflicts`](Self::flag_conflicts).
+    pub fn set_flag_conflicts(&mut self, enabled: bool) -> &mut Self {
+        self.flag_conflicts = enabled;
+        self
+    }
+
+    /// Enable or disable [`detect_script`](Self::detect_script).
+    pub fn set_detect_script(&mut self, enabled: bool) -> &mut Self {
+        self.detect_script = enabled;
+        self
+    }
+
+    /// Enable or disable [`detect_modelines`](Self::detect_modelines).
+    pub fn set_detect_modelines(&mut self, enabled: bool) -> &mut Self {
+        self.detect_modelines = enabled;
+        self
+    }
+
+    /// Enable or disable [`validate_utf8`](Self::validate_utf8).
+    pub fn set_validate_utf8(&mut self, enabled: bool) -> &mut Self {
+        self.validate_utf8 = enabled;
+        self
+    }
+
+    /// Enable or disable [`treat_blank_as_empty`](Self::treat_blank_as_empty).
+    pub fn set_treat_blank_as_empty(&mut self, enabled: bool) -> &mut Self {
+        self.treat_blank_as_empty = enabled;
+        self
+    }
+
+    /// Enable or disable [`fast_language_only`](Self::fast_language_only).
+    pub fn set_fast_language_only(&mut self, enabled: bool) -> &mut Self {
+        self.fast_language_only = enabled;
+        self
+    }
+
+    /// Enable or disable [`tag_unknown`](Self::tag_unknown).
+    pub fn set_tag_unknown(&mut self, enabled: bool) -> &mut Self {
+        self.tag_unknown = enabled;
+        self
+    }
+
+    /// Enable or disable [`tag_hidden`](Self::tag_hidden).
+    pub fn set_tag_hidden(&mut self, enabled: bool) -> &mut Self {
+        self.tag_hidden = enabled;
+        self
+    }
+
+    /// Enable or disable [`treat_special_as_file`](Self::treat_special_as_file).
+    pub fn set_treat_special_as_file(&mut self, enabled: bool) -> &mut Self {
+        self.treat_special_as_file = enabled;
+        self
+    }
+
+    /// Enable or disable [`tag_unknown_shebang_as_script`](Self::tag_unknown_shebang_as_script).
+    pub fn set_tag_unknown_shebang_as_script(&mut self, enabled: bool) -> &mut Self {
+        self.tag_unknown_shebang_as_script = enabled;
+        self
+    }
+
+    /// Enable or disable [`resolve_polyglot_shebangs`](Self::resolve_polyglot_shebangs).
+    pub fn set_resolve_polyglot_shebangs(&mut self, enabled: bool) -> &mut Self {
+        self.resolve_polyglot_shebangs = enabled;
+        self
+    }
+
+    /// Enable or disable [`resolve_node_module_type`](Self::resolve_node_module_type).
+    pub fn set_resolve_node_module_type(&mut self, enabled: bool) -> &mut Self {
+        self.resolve_node_module_type = enabled;
+        self
+    }
+
+    /// Set or remove the [`max_content_bytes`](Self::max_content_bytes) limit.
+    pub fn set_max_content_bytes(&mut self, limit: Option<u64>) -> &mut Self {
+        self.max_content_bytes = limit;
+        self
+    }
+
+    /// Set the [`content_sniff_bytes`](Self::content_sniff_bytes) window.
+    pub fn set_content_sniff_bytes(&mut self, bytes: usize) -> &mut Self {
+        self.content_sniff_bytes = bytes.max(1);
+        self
+    }
+
+    /// Set the [`executable_policy`](Self::executable_policy).
+    pub fn set_executable_policy(&mut self, policy: ExecutablePolicy) -> &mut Self {
+        self.executable_policy = policy;
+        self
+    }
+
+    /// Set the [`on_content_error`](Self::on_content_error) policy.
+    pub fn set_content_error_policy(&mut self, policy: ContentErrorPolicy) -> &mut Self {
+        self.content_error_policy = policy;
+        self
+    }
+
+    /// Set or remove the [`max_symlink_depth`](Self::max_symlink_depth) limit.
+    pub fn set_max_symlink_depth(&mut self, depth: Option<usize>) -> &mut Self {
+        self.max_symlink_depth = depth;
+        self
+    }
+
+    /// Enable or disable [`peek_inside_archives`](Self::peek_inside_archives).
+    ///
+    /// Requires the `flate2` feature.
+    #[cfg(feature = "flate2")]
+    pub fn set_peek_inside_archives(&mut self, enabled: bool) -> &mut Self {
+        self.peek_inside_archives = enabled;
+        self
+    }
+
+    /// Enable or disable [`inspect_zip`](Self::inspect_zip).
+    ///
+    /// Requires the `zip` feature.
+    #[cfg(feature = "zip")]
+    pub fn set_inspect_zip(&mut self, enabled: bool) -> &mut Self {
+        self.inspect_zip = enabled;
+        self
+    }
+}
+
+/// The analysis stage that added a tag, as reported by
+/// [`FileIdentifier::identify_explained`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagOrigin {
+    /// File system metadata: the file type, or a symlink loop.
+    FileType,
+    /// The executable decision.
+    Permission,
+    /// The exact filename, a filename pattern or a path convention.
+    Name,
+    /// The file extension.
+    Extension,
+    /// The shebang interpreter.
+    Shebang,
+    /// The file's content: encoding, magic bytes and opt-in content checks.
+    Content,
+    /// User configuration: custom extensions, filename patterns or magic
+    /// signatures, and the opt-in `unknown` tag.
+    Custom,
+}
+
+/// Time spent in each analysis stage, as reported by
+/// [`FileIdentifier::identify_timed`].
+///
+/// Stages that did not run for a file report zero. The content sample is
+/// read lazily, so reading it is charged to the first stage that needs it:
+/// usually content analysis, but shebang analysis for executables, and
+/// permission analysis under [`ExecutablePolicy::ShebangPresence`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageTimings {
+    /// Reading file metadata and classifying the file type.
+    pub metadata: Duration,
+    /// Deciding whether the file is executable.
+    pub permission: Duration,
+    /// Matching the filename, extension and path conventions.
+    pub filename: Duration,
+    /// Parsing the shebang line.
+    pub shebang: Duration,
+    /// Content analysis: encoding, magic bytes and the opt-in content checks.
+    pub content: Duration,
+}
+
+impl StageTimings {
+    /// The total time spent across all stages.
+    pub fn total(&self) -> Duration {
+        self.metadata + self.permission + self.filename + self.shebang + self.content
+    }
+}
+
+/// A tag set that also records the origin of each tag, in insertion order.
+#[derive(Debug, Default)]
+struct ExplainedTags {
+    tags: TagSet,
+    origins: Vec<(&'static str, TagOrigin)>,
+    timings: StageTimings,
+}
+
+impl ExplainedTags {
+    fn insert(&mut self, tag: &'static str, origin: TagOrigin) {
+        if self.tags.insert(tag) {
+            self.origins.push((tag, origin));
+        }
+    }
+
+    fn extend<I: IntoIterator<Item = &'static str>>(&mut self, tags: I, origin: TagOrigin) {
+        for tag in tags {
+            self.insert(tag, origin);
+        }
+    }
+
+    fn contains(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.tags.retain(|tag| keep(tag));
+        self.origins.retain(|(tag, _)| keep(tag));
+    }
+
+    fn into_tags(self) -> TagSet {
+        self.tags
+    }
+
+    fn into_explained(self) -> Vec<(String, TagOrigin)> {
+        self.origins
+            .into_iter()
+            .map(|(tag, origin)| (tag.to_string(), origin))
+            .collect()
+    }
+}
+
+/// Default number of leading content bytes sampled for content analysis.
+const CONTENT_SAMPLE_SIZE: usize = 1024;
+
+/// Lazily-read leading bytes of a file's content, shared across analysis steps.
+///
+/// The content is opened and read (up to `limit` bytes) at most once, on
+/// first use.
+/// The backing buffer can be handed back and reused for the next sample.
+struct ContentSample<F> {
+    open: F,
+    buffer: Vec<u8>,
+    limit: usize,
+    filled: bool,
+}
+
+impl<F, R> ContentSample<F>
+where
+    F: FnMut() -> std::io::Result<R>,
+    R: Read,
+{
+    fn new(open: F, limit: usize) -> Self {
+        Self::with_buffer(open, Vec::with_capacity(limit), limit)
+    }
+
+    fn with_buffer(open: F, buffer: Vec<u8>, limit: usize) -> Self {
+        Self {
+            open,
+            buffer,
+            limit,
+            filled: false,
+        }
+    }
+
+    fn bytes(&mut self) -> std::io::Result<&[u8]> {
+        if !self.filled {
+            self.buffer.clear();
+            (self.open)()?
+                .take(self.limit as u64)
+                .read_to_end(&mut self.buffer)?;
+            self.filled = true;
+        }
+        Ok(&self.buffer)
+    }
+
+    fn into_buffer(self) -> Vec<u8> {
+        self.buffer
+    }
+}
 
 /// Result type for file identification operations.
 ///
@@ -362,6 +1858,14 @@ pub enum IdentifyError {
         source: std::io::Error,
     },
 
+    /// An I/O error occurred while reading a specific file.
+    #[error("IO error at {path}: {source}")]
+    IoErrorAt {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
     /// The file path contains invalid UTF-8 sequences.
     #[error("Path contains invalid UTF-8: {path}")]
     InvalidPath { path: String },
@@ -369,11 +1873,28 @@ pub enum IdentifyError {
     /// The file content is not valid UTF-8 when UTF-8 is expected.
     #[error("File contains invalid UTF-8 content")]
     InvalidUtf8,
+
+    /// A configuration file could not be parsed.
+    #[error("Invalid configuration: {message}")]
+    InvalidConfig { message: String },
+}
+
+impl IdentifyError {
+    /// Attach a path to a bare I/O error, leaving other errors unchanged.
+    fn at_path(self, path: &Path) -> Self {
+        match self {
+            IdentifyError::IoError { source } => IdentifyError::IoErrorAt {
+                path: path.to_string_lossy().to_string(),
+                source,
+            },
+            other => other,
+        }
+    }
 }
 
 /// Analyze file system metadata to determine basic file type.
 ///
-/// Returns tags for directory, symlink, socket, or file based on metadata.
+/// Returns tags for directory, symlink, socket, fifo, or device based on metadata.
 /// This is the first step in file identification.
 fn analyze_file_type(metadata: &std::fs::Metadata) -> Option<TagSet> {
     let file_type = metadata.file_type();
@@ -385,19 +1906,49 @@ fn analyze_file_type(metadata: &std::fs::Metadata) -> Option<TagSet> {
         return Some([SYMLINK].iter().cloned().collect());
     }
 
-    // Check for socket (Unix-specific)
+    // Check for sockets, FIFOs and devices (Unix-specific)
     #[cfg(unix)]
     {
         use std::os::unix::fs::FileTypeExt;
         if file_type.is_socket() {
             return Some([SOCKET].iter().cloned().collect());
         }
+        if file_type.is_fifo() {
+            return Some([FIFO].iter().cloned().collect());
+        }
+        if file_type.is_block_device() {
+            return Some([BLOCK_DEVICE].iter().cloned().collect());
+        }
+        if file_type.is_char_device() {
+            return Some([CHAR_DEVICE].iter().cloned().collect());
+        }
     }
 
     // Regular file - continue with further analysis
     None
 }
 
+/// Check whether resolving a symlink chain takes more than `max_depth` links.
+///
+/// Dangling and unreadable links end the chain without counting as a loop.
+fn symlink_chain_exceeds(path: &Path, max_depth: usize) -> bool {
+    let mut current = path.to_path_buf();
+    for _ in 0..max_depth {
+        let Ok(target) = fs::read_link(&current) else {
+            return false;
+        };
+        current = match current.parent() {
+            Some(parent) => parent.join(target),
+            None => target,
+        };
+        let is_symlink = fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink());
+        if !is_symlink {
+            return false;
+        }
+    }
+    true
+}
+
 /// Analyze file permissions to determine executable status.
 ///
 /// Returns true if the file is executable, false otherwise.
@@ -411,16 +1962,55 @@ fn analyze_permissions<P: AsRef<Path>>(path: P, metadata: &std::fs::Metadata) ->
     }
     #[cfg(not(unix))]
     {
-        // On non-Unix systems, check file extension for common executables
+        // On non-Unix systems, check file extension against PATHEXT
         let _ = metadata; // Suppress unused warning on non-Unix
-        let path = path.as_ref();
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| matches!(ext.to_lowercase().as_str(), "exe" | "bat" | "cmd"))
-            .unwrap_or(false)
+        let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| DEFAULT_PATHEXT.to_string());
+        extension_in_pathext(path.as_ref(), &pathext)
+    }
+}
+
+/// Check whether a file name follows the Unix dotfile convention for hidden files.
+fn has_hidden_name(name: Option<&OsStr>) -> bool {
+    name.and_then(OsStr::to_str)
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// Check whether file metadata carries the Windows hidden attribute.
+fn has_hidden_attribute(metadata: &std::fs::Metadata) -> bool {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = metadata; // Only Windows has a hidden attribute
+        false
     }
 }
 
+/// Executable extensions used when `PATHEXT` is not set (the Windows default).
+#[cfg(any(not(unix), test))]
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD;.VBS;.VBE;.JS;.JSE;.WSF;.WSH;.MSC";
+
+/// Check whether a path's extension is listed in a `PATHEXT`-style string.
+///
+/// Entries are separated by `;` and compared case-insensitively, with or
+/// without their leading dot.
+#[cfg(any(not(unix), test))]
+fn extension_in_pathext(path: &Path, pathext: &str) -> bool {
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+
+    pathext
+        .split(';')
+        .map(|entry| entry.trim().trim_start_matches('.'))
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| entry.eq_ignore_ascii_case(ext))
+}
+
 /// Analyze filename and potentially shebang for file type identification.
 ///
 /// First tries filename-based identification. If that fails and the file is executable,
@@ -430,8 +2020,8 @@ fn analyze_filename_and_shebang<P: AsRef<Path>>(path: P, is_executable: bool) ->
     let mut tags = TagSet::new();
 
     // Check filename-based tags first
-    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-        let filename_tags = tags_from_filename(filename);
+    if let Some(filename) = path.file_name() {
+        let filename_tags = tags_from_os_filename(filename);
         if !filename_tags.is_empty() {
             tags.extend(filename_tags);
         } else if is_executable {
@@ -450,20 +2040,268 @@ fn analyze_filename_and_shebang<P: AsRef<Path>>(path: P, is_executable: bool) ->
 
 /// Analyze file content to determine encoding (text vs binary).
 ///
-/// Only performs analysis if encoding tags are not already present.
+/// Only performs analysis if encoding tags are not already present. Backs the
+/// unconfigured free functions, so it always samples the default window.
 fn analyze_content_encoding<P: AsRef<Path>>(path: P, existing_tags: &TagSet) -> Result<TagSet> {
-    let mut tags = TagSet::new();
+    let path = path.as_ref();
 
     // Check if we need to determine binary vs text
-    if !existing_tags.iter().any(|tag| ENCODING_TAGS.contains(tag)) {
-        if file_is_text(path)? {
-            tags.insert(TEXT);
-        } else {
-            tags.insert(BINARY);
+    if existing_tags.iter().any(|tag| ENCODING_TAGS.contains(tag)) {
+        return Ok(TagSet::new());
+    }
+
+    let mut header = Vec::with_capacity(CONTENT_SAMPLE_SIZE);
+    fs::File::open(path)
+        .and_then(|file| {
+            file.take(CONTENT_SAMPLE_SIZE as u64)
+                .read_to_end(&mut header)
+        })
+        .map_err(|e| IdentifyError::from(e).at_path(path))?;
+    encoding_tags_from_sample(&header)
+}
+
+/// Determine encoding tags for a content sample.
+///
+/// A recognized magic signature supplies the tags (including the format, such
+/// as `png` or `elf`); otherwise the sample is classified as text or binary.
+fn encoding_tags_from_sample(sample: &[u8]) -> Result<TagSet> {
+    let magic_tags = get_magic_tags(sample);
+    if !magic_tags.is_empty() {
+        return Ok(magic_tags);
+    }
+
+    Ok(TagSet::from([if bytes_are_text(sample) {
+        TEXT
+    } else {
+        BINARY
+    }]))
+}
+
+/// Identify the decompressed content of a single-member gzip sample.
+///
+/// Returns format tags only; encoding tags of the inner content are dropped.
+/// Tar archives are skipped to keep the work bounded.
+#[cfg(feature = "flate2")]
+fn gzip_inner_tags(filename: Option<&str>, compressed: &[u8], window: usize) -> TagSet {
+    let inner_name = filename.and_then(|name| name.strip_suffix(".gz"));
+    let is_tarball = filename.is_some_and(|name| name.ends_with(".tgz"))
+        || inner_name.is_some_and(|name| name.ends_with(".tar"));
+    if is_tarball {
+        return TagSet::new();
+    }
+
+    // The sample may end mid-stream; keep whatever decompressed cleanly
+    let mut inner = Vec::with_capacity(window);
+    let _ = flate2::read::GzDecoder::new(compressed)
+        .take(window as u64)
+        .read_to_end(&mut inner);
+    if inner.is_empty() {
+        return TagSet::new();
+    }
+
+    let mut tags = tags_from_content(inner.as_slice()).unwrap_or_default();
+    if tags.contains("tar") {
+        return TagSet::new();
+    }
+    if let Some(name) = inner_name {
+        tags.extend(tags_from_filename(name));
+    }
+    tags.retain(|tag| !is_encoding_tag(tag));
+    tags
+}
+
+/// Entries whose presence identifies a ZIP-based container format, checked in
+/// order. APKs are signed like jars, so `AndroidManifest.xml` comes first.
+#[cfg(feature = "zip")]
+const ZIP_MARKER_ENTRIES: &[(&str, &str)] = &[
+    ("AndroidManifest.xml", "apk"),
+    ("[Content_Types].xml", "ooxml"),
+    ("META-INF/MANIFEST.MF", "jar"),
+];
+
+/// Identify a ZIP container from its central directory's entry names.
+///
+/// Returns `None` if the file is not a readable ZIP archive or has no marker
+/// entry. Entry contents are never decompressed.
+#[cfg(feature = "zip")]
+fn zip_container_tag(path: &Path) -> Option<&'static str> {
+    let file = fs::File::open(path).ok()?;
+    let archive = zip::ZipArchive::new(std::io::BufReader::new(file)).ok()?;
+    ZIP_MARKER_ENTRIES
+        .iter()
+        .find(|(entry, _)| archive.index_for_name(entry).is_some())
+        .map(|&(_, tag)| tag)
+}
+
+/// Tags that mark a property of a file without identifying its format.
+const MARKER_TAGS: &[&str] = &[
+    HIDDEN,
+    UNKNOWN,
+    SYMLINK_LOOP,
+    CONTENT_MISMATCH,
+    VALID_UTF8,
+    INVALID_UTF8,
+    BLANK,
+    SCRIPT,
+    "minified",
+    "latin",
+    "cjk",
+    "cyrillic",
+    "arabic",
+    "mixed-script",
+];
+
+/// Check whether a tag describes a file format rather than the file's type,
+/// mode, encoding or another marker property.
+fn is_format_tag(tag: &str) -> bool {
+    !is_type_tag(tag) && !is_mode_tag(tag) && !is_encoding_tag(tag) && !MARKER_TAGS.contains(&tag)
+}
+
+/// Check whether content is only an optional UTF-8 BOM and ASCII whitespace.
+fn is_blank(content: &[u8]) -> bool {
+    content
+        .strip_prefix(b"\xef\xbb\xbf")
+        .unwrap_or(content)
+        .iter()
+        .all(u8::is_ascii_whitespace)
+}
+
+/// Check whether a content sample is valid UTF-8.
+///
+/// A multi-byte sequence left incomplete at the end of a full sample window
+/// of `window` bytes may continue past it, so it is not treated as invalid.
+fn is_valid_utf8_sample(sample: &[u8], window: usize) -> bool {
+    match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none() && sample.len() >= window,
+    }
+}
+
+/// Determine the dominant writing script of a content sample.
+///
+/// Returns `None` if the sample is not valid UTF-8 or contains no letters.
+fn dominant_script_of_sample(sample: &[u8]) -> Option<&'static str> {
+    let text = match std::str::from_utf8(sample) {
+        Ok(text) => text,
+        // A multi-byte sequence cut off by the sample window is not an error
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&sample[..e.valid_up_to()]).unwrap_or_default()
         }
+        Err(_) => return None,
+    };
+
+    dominant_script(text)
+}
+
+/// Number of lines at each end of a file scanned for modelines, matching
+/// Vim's default `modelines` setting.
+const MODELINE_LINES: usize = 5;
+
+/// Find the language declared by a Vim or Emacs modeline in a content sample.
+fn modeline_language(sample: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(sample);
+    let lines: Vec<&str> = text.lines().collect();
+    let tail_start = lines
+        .len()
+        .saturating_sub(MODELINE_LINES)
+        .max(MODELINE_LINES);
+    lines
+        .iter()
+        .take(MODELINE_LINES)
+        .chain(lines.iter().skip(tail_start))
+        .find_map(|line| vim_modeline_filetype(line).or_else(|| emacs_mode(line)))
+        .map(|language| language.to_lowercase())
+}
+
+/// Extract the filetype from a Vim modeline such as `# vim: set ft=ruby :`.
+fn vim_modeline_filetype(line: &str) -> Option<&str> {
+    let options = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+        let start = line.find(marker)?;
+        // The marker must start the line or follow whitespace
+        let at_boundary = line[..start]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        at_boundary.then(|| &line[start + marker.len()..])
+    })?;
+
+    let options = options.trim_start();
+    let options = options
+        .strip_prefix("set ")
+        .or_else(|| options.strip_prefix("se "))
+        .unwrap_or(options);
+    options
+        .split(|c: char| c == ':' || c.is_whitespace())
+        .find_map(|option| {
+            let (key, value) = option.split_once('=')?;
+            matches!(key, "ft" | "filetype" | "syn" | "syntax").then_some(value)
+        })
+        .filter(|value| !value.is_empty())
+}
+
+/// Extract the major mode from an Emacs mode line such as `-*- mode: ruby -*-`.
+fn emacs_mode(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once("-*-")?;
+    let (variables, _) = rest.split_once("-*-")?;
+    let variables = variables.trim();
+    if !variables.contains(':') {
+        // `-*- ruby -*-` names the mode directly
+        return (!variables.is_empty()).then_some(variables);
     }
+    variables.split(';').find_map(|variable| {
+        let (key, value) = variable.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("mode")
+            .then(|| value.trim())
+            .filter(|value| !value.is_empty())
+    })
+}
 
-    Ok(tags)
+/// Resolve a language name from a modeline to format tags.
+///
+/// Tries the name as an interpreter, then as an extension, then as a known
+/// language tag. Encoding tags are not included.
+fn language_tags_from_name(language: &str) -> TagSet {
+    let mut tags = tags_from_interpreter(language);
+    if tags.is_empty() {
+        tags = get_extension_tags(language);
+    }
+    if tags.is_empty() {
+        if let Some(&tag) = LANGUAGE_PRIORITY.iter().find(|&&tag| tag == language) {
+            tags.insert(tag);
+        }
+    }
+    tags.retain(|tag| !is_encoding_tag(tag));
+    tags
+}
+
+/// Classify text by the Unicode script of the majority of its letters.
+fn dominant_script(text: &str) -> Option<&'static str> {
+    let mut counts = [0usize; 4];
+    let scripts = ["latin", "cjk", "cyrillic", "arabic"];
+
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        let index = match u32::from(c) {
+            0x0041..=0x024F | 0x1E00..=0x1EFF => 0,
+            0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF => 1,
+            0x0400..=0x052F => 2,
+            0x0600..=0x06FF | 0x0750..=0x077F => 3,
+            _ => continue,
+        };
+        counts[index] += 1;
+    }
+
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return None;
+    }
+
+    let (index, &count) = counts.iter().enumerate().max_by_key(|&(_, count)| count)?;
+    if count * 2 > total {
+        Some(scripts[index])
+    } else {
+        Some("mixed-script")
+    }
 }
 
 /// Identify a file from its filesystem path.
@@ -471,7 +2309,7 @@ fn analyze_content_encoding<P: AsRef<Path>>(path: P, existing_tags: &TagSet) ->
 /// This is the most comprehensive identification method, providing a superset
 /// of information from other methods. It analyzes:
 ///
-/// 1. File type (regular file, directory, symlink, socket)
+/// 1. File type (regular file, directory, symlink, socket, fifo, device)
 /// 2. File permissions (executable vs non-executable)
 /// 3. Filename and extension patterns
 /// 4. File content (binary vs text detection)
@@ -488,7 +2326,7 @@ fn analyze_content_encoding<P: AsRef<Path>>(path: P, existing_tags: &TagSet) ->
 /// # Errors
 ///
 /// Returns [`IdentifyError::PathNotFound`] if the path doesn't exist, or
-/// [`IdentifyError::IoError`] for other I/O failures.
+/// [`IdentifyError::IoErrorAt`] if reading the file fails.
 ///
 /// # Examples
 ///
@@ -519,7 +2357,7 @@ pub fn tags_from_path<P: AsRef<Path>>(path: P) -> Result<TagSet> {
         }
     };
 
-    // Step 1: Check for non-regular file types (directory, symlink, socket)
+    // Step 1: Check for non-regular file types (directory, symlink, socket, fifo, device)
     if let Some(file_type_tags) = analyze_file_type(&metadata) {
         return Ok(file_type_tags);
     }
@@ -539,6 +2377,7 @@ pub fn tags_from_path<P: AsRef<Path>>(path: P) -> Result<TagSet> {
     // Step 4: Analyze filename and potentially shebang
     let filename_and_shebang_tags = analyze_filename_and_shebang(path, is_executable);
     tags.extend(filename_and_shebang_tags);
+    tags.extend(get_path_tags(path));
 
     // Step 5: Analyze content encoding (text vs binary) if not already determined
     let encoding_tags = analyze_content_encoding(path, &tags)?;
@@ -547,10 +2386,366 @@ pub fn tags_from_path<P: AsRef<Path>>(path: P) -> Result<TagSet> {
     Ok(tags)
 }
 
-/// Identify a file based only on its filename.
+/// Check whether a path would be tagged `executable`, without reading content.
 ///
-/// This method analyzes the filename and extension to determine file type,
-/// without accessing the filesystem. It's useful when you only have the
+/// Uses the same decision as [`tags_from_path`]: permission bits on Unix and
+/// the `PATHEXT` environment variable elsewhere. Symlinks are followed, so
+/// the result describes the link's target.
+///
+/// # Errors
+///
+/// Returns [`IdentifyError::PathNotFound`] if the path does not exist.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::is_executable;
+/// # use std::fs;
+/// # use tempfile::tempdir;
+///
+/// # let dir = tempdir().unwrap();
+/// # let file_path = dir.path().join("notes.txt");
+/// # fs::write(&file_path, "hello").unwrap();
+/// assert!(!is_executable(&file_path).unwrap());
+/// assert!(is_executable("/nonexistent/path").is_err());
+/// ```
+pub fn is_executable<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let path = path.as_ref();
+    let metadata = fs::metadata(path).map_err(|_| IdentifyError::PathNotFound {
+        path: path.to_string_lossy().to_string(),
+    })?;
+    Ok(analyze_permissions(path, &metadata))
+}
+
+/// Identify only the type and mode of a path, from its metadata.
+///
+/// The cheapest identification: a single `symlink_metadata` call, with no
+/// filename, shebang or content analysis, so the file is never opened.
+/// Regular files get `file` and `executable` or `non-executable`; other file
+/// types get their type tag, as from [`tags_from_path`]. Symlinks are not
+/// followed.
+///
+/// # Errors
+///
+/// Returns [`IdentifyError::PathNotFound`] if the path does not exist.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_metadata;
+/// # use std::fs;
+/// # use tempfile::tempdir;
+///
+/// # let dir = tempdir().unwrap();
+/// # let file_path = dir.path().join("script.py");
+/// # fs::write(&file_path, "print('hello')").unwrap();
+/// let tags = tags_from_metadata(&file_path).unwrap();
+/// assert_eq!(tags, ["file", "non-executable"].into_iter().collect());
+/// ```
+pub fn tags_from_metadata<P: AsRef<Path>>(path: P) -> Result<TagSet> {
+    let path = path.as_ref();
+    let metadata = fs::symlink_metadata(path).map_err(|_| IdentifyError::PathNotFound {
+        path: path.to_string_lossy().to_string(),
+    })?;
+    if let Some(file_type_tags) = analyze_file_type(&metadata) {
+        return Ok(file_type_tags);
+    }
+
+    let mode = if analyze_permissions(path, &metadata) {
+        EXECUTABLE
+    } else {
+        NON_EXECUTABLE
+    };
+    Ok(TagSet::from([FILE, mode]))
+}
+
+/// Identify a file from its path, returning the tags sorted alphabetically.
+///
+/// Same as [`tags_from_path`], but with a deterministic order suitable for
+/// snapshot tests and human-readable output.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_path_sorted;
+/// # use std::fs;
+/// # use tempfile::tempdir;
+///
+/// # let dir = tempdir().unwrap();
+/// # let file_path = dir.path().join("script.py");
+/// # fs::write(&file_path, "print('hello')").unwrap();
+/// let tags = tags_from_path_sorted(&file_path).unwrap();
+/// assert_eq!(tags, ["file", "non-executable", "python", "text"]);
+/// ```
+pub fn tags_from_path_sorted<P: AsRef<Path>>(path: P) -> Result<Vec<&'static str>> {
+    tags_from_path(path).map(|tags| sorted_tags(&tags))
+}
+
+/// Check whether [`tags_from_path`] would include `tag`, doing only the work needed.
+///
+/// Analysis stops as soon as the answer is known:
+/// - File type and mode tags only need file metadata.
+/// - Format tags are answered from the filename, and the shebang for
+///   executables, when the name determines the file's encoding; content is
+///   read only when it could still add the tag.
+/// - Encoding tags such as `text` read content unless the name decides them.
+///
+/// # Errors
+///
+/// Returns [`IdentifyError::PathNotFound`] if the path does not exist, or
+/// [`IdentifyError::IoErrorAt`] if reading the file fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::path_has_tag;
+/// # use std::fs;
+/// # use tempfile::tempdir;
+///
+/// # let dir = tempdir().unwrap();
+/// # let file_path = dir.path().join("setup.py");
+/// # fs::write(&file_path, "print('hello')").unwrap();
+/// assert!(path_has_tag(&file_path, "python").unwrap());
+/// assert!(!path_has_tag(&file_path, "ruby").unwrap());
+/// ```
+pub fn path_has_tag<P: AsRef<Path>>(path: P, tag: &str) -> Result<bool> {
+    let path = path.as_ref();
+    let metadata = fs::symlink_metadata(path).map_err(|_| IdentifyError::PathNotFound {
+        path: path.to_string_lossy().to_string(),
+    })?;
+
+    if let Some(file_type_tags) = analyze_file_type(&metadata) {
+        return Ok(file_type_tags.contains(tag));
+    }
+    if is_type_tag(tag) {
+        return Ok(tag == FILE);
+    }
+
+    let is_executable = analyze_permissions(path, &metadata);
+    if is_mode_tag(tag) {
+        return Ok((tag == EXECUTABLE) == is_executable);
+    }
+
+    let mut tags = analyze_filename_and_shebang(path, is_executable);
+    tags.extend(get_path_tags(path));
+    if tags.contains(tag) {
+        return Ok(true);
+    }
+    // Content is only analyzed when the name leaves the encoding undecided
+    if tags.iter().any(is_encoding_tag) {
+        return Ok(false);
+    }
+    Ok(analyze_content_encoding(path, &tags)?.contains(tag))
+}
+
+/// Walk a directory tree and identify each file, streaming results to a callback.
+///
+/// The callback is invoked once per non-directory entry as the walk proceeds,
+/// so results can be processed without collecting the whole tree in memory.
+/// Symlinks are reported but not followed. Errors for individual entries
+/// (including unreadable directories) are passed to the callback and do not
+/// abort the walk. If `root` is not a directory, the callback is invoked once
+/// for `root` itself.
+///
+/// # Arguments
+///
+/// * `root` - Directory (or file) to start from
+/// * `f` - Callback receiving each path and its identification result
+///
+/// # Errors
+///
+/// Returns [`IdentifyError::PathNotFound`] if `root` doesn't exist.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::for_each_file;
+/// # use std::fs;
+/// # use tempfile::tempdir;
+///
+/// # let dir = tempdir().unwrap();
+/// # fs::write(dir.path().join("main.py"), "print('hello')").unwrap();
+/// # fs::create_dir(dir.path().join("docs")).unwrap();
+/// # fs::write(dir.path().join("docs").join("README.md"), "# Docs").unwrap();
+/// let mut count = 0;
+/// for_each_file(dir.path(), |_path, tags| {
+///     assert!(tags.unwrap().contains("file"));
+///     count += 1;
+/// })
+/// .unwrap();
+/// assert_eq!(count, 2);
+/// ```
+pub fn for_each_file<P, F>(root: P, mut f: F) -> Result<()>
+where
+    P: AsRef<Path>,
+    F: FnMut(&Path, Result<TagSet>),
+{
+    let root = root.as_ref();
+
+    let metadata = match fs::symlink_metadata(root) {
+        Ok(meta) => meta,
+        Err(_) => {
+            return Err(IdentifyError::PathNotFound {
+                path: root.to_string_lossy().to_string(),
+            });
+        }
+    };
+
+    if !metadata.is_dir() {
+        f(root, tags_from_path(root));
+        return Ok(());
+    }
+
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                f(&dir, Err(e.into()));
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    f(&dir, Err(e.into()));
+                    continue;
+                }
+            };
+
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => pending.push(path),
+                _ => f(&path, tags_from_path(&path)),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Identify data based only on its content, ignoring any filename.
+///
+/// This method reads up to 1KB, the default
+/// [`content_sniff_bytes`](FileIdentifier::content_sniff_bytes) window, from
+/// the reader and runs magic-byte, shebang, and text vs binary analysis. It
+/// never consults extensions or special filenames, which makes it suitable
+/// when names cannot be trusted (e.g. a renamed executable uploaded as
+/// `malware.txt`).
+///
+/// Format tags derived purely from content may be incomplete compared to
+/// extension-based detection: many text formats have no reliable signature,
+/// so they will only be tagged `text`.
+///
+/// # Arguments
+///
+/// * `reader` - A reader providing the data to analyze
+///
+/// # Returns
+///
+/// A set of tags identifying the content. Always contains exactly one of
+/// `text` or `binary`.
+///
+/// # Errors
+///
+/// Returns [`IdentifyError::IoError`] if reading fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_content;
+/// use std::io::Cursor;
+///
+/// let tags = tags_from_content(Cursor::new(b"\x7fELF\x02\x01\x01\x00")).unwrap();
+/// assert!(tags.contains("binary"));
+/// assert!(tags.contains("elf"));
+///
+/// let tags = tags_from_content(Cursor::new(b"#!/usr/bin/env python3\nprint('hi')")).unwrap();
+/// assert!(tags.contains("python"));
+/// assert!(tags.contains("text"));
+/// ```
+pub fn tags_from_content<R: Read>(reader: R) -> Result<TagSet> {
+    let mut header = Vec::with_capacity(CONTENT_SAMPLE_SIZE);
+    reader
+        .take(CONTENT_SAMPLE_SIZE as u64)
+        .read_to_end(&mut header)?;
+
+    let mut tags = encoding_tags_from_sample(&header)?;
+
+    let shebang_components = parse_shebang(header.as_slice())?;
+    if let Some(interpreter) = shebang_components.first() {
+        tags.extend(tags_from_interpreter(interpreter));
+    }
+
+    Ok(tags)
+}
+
+/// Identify an already-open file descriptor on Unix.
+///
+/// Runs filename analysis on `filename`, then reads up to 1KB, the default
+/// [`content_sniff_bytes`](FileIdentifier::content_sniff_bytes) window, from
+/// `fd`. When the filename is not recognized, the content gets the same
+/// magic number, shebang and encoding analysis as [`tags_from_content`];
+/// otherwise it is only used for magic number and text vs binary detection
+/// when the filename doesn't already determine the encoding. No file type or permission tags are
+/// produced, so this works for pipes and sockets as well as regular files.
+///
+/// # Ownership
+///
+/// The descriptor is borrowed, not taken: it is never closed by this
+/// function and remains owned by the caller. Reading advances the
+/// descriptor's position, and for pipes or sockets the consumed bytes are
+/// not available to later reads.
+///
+/// # Errors
+///
+/// Returns [`IdentifyError::IoError`] if reading from the descriptor fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_fd;
+/// use std::process::{Command, Stdio};
+///
+/// let mut child = Command::new("echo")
+///     .arg("#!/usr/bin/env python3")
+///     .stdout(Stdio::piped())
+///     .spawn()
+///     .unwrap();
+/// let stdout = child.stdout.take().unwrap();
+///
+/// let tags = tags_from_fd("-", &stdout).unwrap();
+/// assert!(tags.contains("python"));
+/// assert!(tags.contains("text"));
+/// # child.wait().unwrap();
+/// ```
+#[cfg(unix)]
+pub fn tags_from_fd(filename: &str, fd: impl std::os::fd::AsFd) -> Result<TagSet> {
+    // Read through a duplicate, which shares the caller's file position
+    let file = fs::File::from(fd.as_fd().try_clone_to_owned()?);
+
+    let mut header = Vec::with_capacity(CONTENT_SAMPLE_SIZE);
+    file.take(CONTENT_SAMPLE_SIZE as u64)
+        .read_to_end(&mut header)?;
+
+    let mut tags = tags_from_filename(filename);
+    if tags.is_empty() {
+        return tags_from_content(header.as_slice());
+    }
+
+    if !tags.iter().any(is_encoding_tag) {
+        tags.extend(encoding_tags_from_sample(&header)?);
+    }
+
+    Ok(tags)
+}
+
+/// Identify a file based only on its filename.
+///
+/// This method analyzes the filename and extension to determine file type,
+/// without accessing the filesystem. It's useful when you only have the
 /// filename or want to avoid I/O operations.
 ///
 /// # Arguments
@@ -578,32 +2773,210 @@ pub fn tags_from_path<P: AsRef<Path>>(path: P) -> Result<TagSet> {
 /// assert!(tags.is_empty());
 /// ```
 pub fn tags_from_filename(filename: &str) -> TagSet {
-    let mut tags = TagSet::new();
+    let (mut tags, ext_tags) = filename_tags_by_origin(filename);
+    tags.extend(ext_tags);
+    tags
+}
 
-    // Check exact filename matches first
-    for part in std::iter::once(filename).chain(filename.split('.')) {
-        let name_tags = get_name_tags(part);
-        if !name_tags.is_empty() {
-            tags.extend(name_tags);
+/// Identify a file based only on its filename, given as an OS string.
+///
+/// Like [`tags_from_filename`], but accepts names that are not valid UTF-8.
+/// On Unix such names are matched on their raw bytes, so `caf\xe9.py` is
+/// still tagged `python`: the extension and each dot-separated component
+/// are looked up when they are themselves valid UTF-8. Filename patterns
+/// such as `requirements-*.txt` only apply to UTF-8 names. On other
+/// platforms the name is converted lossily.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_os_filename;
+/// use std::ffi::OsStr;
+///
+/// let tags = tags_from_os_filename(OsStr::new("script.py"));
+/// assert!(tags.contains("python"));
+/// ```
+pub fn tags_from_os_filename(filename: &OsStr) -> TagSet {
+    let (mut tags, ext_tags) = os_filename_tags_by_origin(filename);
+    tags.extend(ext_tags);
+    tags
+}
+
+/// Like [`filename_tags_by_origin`], but for a filename that may not be UTF-8.
+fn os_filename_tags_by_origin(filename: &OsStr) -> (TagSet, TagSet) {
+    if let Some(filename) = filename.to_str() {
+        return filename_tags_by_origin(filename);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        filename_bytes_tags_by_origin(filename.as_bytes())
+    }
+    #[cfg(not(unix))]
+    {
+        filename_tags_by_origin(&filename.to_string_lossy())
+    }
+}
+
+/// Identify a non-UTF-8 filename from the UTF-8 parts of its raw bytes.
+#[cfg(unix)]
+fn filename_bytes_tags_by_origin(filename: &[u8]) -> (TagSet, TagSet) {
+    let mut name_tags = TagSet::new();
+
+    // The full name is not UTF-8, so only its components can match names
+    if let Some(part_tags) = filename
+        .split(|&b| b == b'.')
+        .filter_map(|part| std::str::from_utf8(part).ok())
+        .filter(|part| !part.is_empty())
+        .map(get_name_tags)
+        .find(|part_tags| !part_tags.is_empty())
+    {
+        name_tags.extend(part_tags);
+    }
+
+    // Same rule as `Path::extension`: a leading dot does not start one
+    let ext = filename
+        .iter()
+        .rposition(|&b| b == b'.')
+        .filter(|&dot| dot > 0)
+        .and_then(|dot| std::str::from_utf8(&filename[dot + 1..]).ok());
+    let mut ext_tags = TagSet::new();
+    if let Some(ext) = ext {
+        let ext_lower = ext.to_lowercase();
+        ext_tags = get_extension_tags(&ext_lower);
+        if ext_tags.is_empty() {
+            ext_tags = get_extensions_need_binary_check_tags(&ext_lower);
+        }
+    }
+
+    (name_tags, ext_tags)
+}
+
+/// Identify a file based only on its filename, tagging unrecognized names `unknown`.
+///
+/// Same as [`tags_from_filename`], but returns `{"unknown"}` instead of an
+/// empty set when the filename is not recognized.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_filename_or_unknown;
+///
+/// assert!(tags_from_filename_or_unknown("script.py").contains("python"));
+/// assert_eq!(
+///     tags_from_filename_or_unknown("unknown.xyz"),
+///     ["unknown"].into_iter().collect()
+/// );
+/// ```
+pub fn tags_from_filename_or_unknown(filename: &str) -> TagSet {
+    let mut tags = tags_from_filename(filename);
+    if tags.is_empty() {
+        tags.insert(UNKNOWN);
+    }
+    tags
+}
+
+/// Identify many files by filename only, returning their tags in input order.
+///
+/// Equivalent to calling [`tags_from_filename`] on each name.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_filenames;
+///
+/// let results = tags_from_filenames(&["main.rs", "unknown.xyz"]);
+/// assert!(results[0].contains("rust"));
+/// assert!(results[1].is_empty());
+/// ```
+pub fn tags_from_filenames(names: &[&str]) -> Vec<TagSet> {
+    names.iter().map(|name| tags_from_filename(name)).collect()
+}
+
+/// Compute filename tags split into name-derived and extension-derived sets.
+fn filename_tags_by_origin(filename: &str) -> (TagSet, TagSet) {
+    let mut name_tags = TagSet::new();
+
+    // Check exact filename matches first: the full name, then each
+    // dot-separated component in order, matching Python's identify. A name
+    // without dots is its own only component, so it is looked up once.
+    let components = filename
+        .contains('.')
+        .then(|| filename.split('.').filter(|part| !part.is_empty()))
+        .into_iter()
+        .flatten();
+    for part in std::iter::once(filename).chain(components) {
+        let part_tags = get_name_tags(part);
+        if !part_tags.is_empty() {
+            name_tags.extend(part_tags);
             break;
         }
     }
 
+    // Check filename patterns such as `requirements-dev.txt`
+    if let Some(name) = Path::new(filename).file_name().and_then(|n| n.to_str()) {
+        name_tags.extend(get_name_pattern_tags(name));
+    }
+
     // Check file extension
+    let mut ext_tags = TagSet::new();
     if let Some(ext) = Path::new(filename).extension().and_then(|e| e.to_str()) {
         let ext_lower = ext.to_lowercase();
 
-        let ext_tags = get_extension_tags(&ext_lower);
-        if !ext_tags.is_empty() {
-            tags.extend(ext_tags);
-        } else {
-            let binary_check_tags = get_extensions_need_binary_check_tags(&ext_lower);
-            if !binary_check_tags.is_empty() {
-                tags.extend(binary_check_tags);
-            }
+        ext_tags = get_extension_tags(&ext_lower);
+        if ext_tags.is_empty() {
+            ext_tags = get_extensions_need_binary_check_tags(&ext_lower);
         }
     }
 
+    (name_tags, ext_tags)
+}
+
+/// Identify a file based only on its filename, returning the tags sorted alphabetically.
+///
+/// Same as [`tags_from_filename`], but with a deterministic order.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_filename_sorted;
+///
+/// assert_eq!(tags_from_filename_sorted("script.py"), ["python", "text"]);
+/// ```
+pub fn tags_from_filename_sorted(filename: &str) -> Vec<&'static str> {
+    sorted_tags(&tags_from_filename(filename))
+}
+
+fn sorted_tags(tags: &TagSet) -> Vec<&'static str> {
+    let mut sorted: Vec<_> = tags.iter().copied().collect();
+    sorted.sort_unstable();
+    sorted
+}
+
+/// Identify a file based on its filename and the directories containing it.
+///
+/// Like [`tags_from_filename`], but also recognizes files by path convention,
+/// such as GitHub Actions workflows under `.github/workflows/`, which the
+/// basename alone cannot identify. Does not access the filesystem.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags_from_filename_with_path;
+///
+/// let tags = tags_from_filename_with_path(".github/workflows/ci.yml");
+/// assert!(tags.contains("yaml"));
+/// assert!(tags.contains("github-workflow"));
+/// ```
+pub fn tags_from_filename_with_path<P: AsRef<Path>>(path: P) -> TagSet {
+    let path = path.as_ref();
+    let mut tags = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(tags_from_filename)
+        .unwrap_or_default();
+    tags.extend(get_path_tags(path));
     tags
 }
 
@@ -611,7 +2984,11 @@ pub fn tags_from_filename(filename: &str) -> TagSet {
 ///
 /// This function analyzes interpreter names from shebang lines to determine
 /// the script type. It handles version-specific interpreters by progressively
-/// removing version suffixes.
+/// removing version suffixes. If no dot-separated prefix is recognized, a
+/// trailing numeric suffix (optionally preceded by a hyphen) is stripped, so
+/// `bash5` and `zsh-5.8` resolve to `bash` and `zsh`. Interpreter paths,
+/// including absolute paths passed through `/usr/bin/env`, resolve via their
+/// basename.
 ///
 /// # Arguments
 ///
@@ -635,6 +3012,9 @@ pub fn tags_from_filename(filename: &str) -> TagSet {
 /// assert!(tags.contains("shell"));
 /// assert!(tags.contains("bash"));
 ///
+/// let tags = tags_from_interpreter("zsh-5.8");
+/// assert!(tags.contains("zsh"));
+///
 /// let tags = tags_from_interpreter("unknown-interpreter");
 /// assert!(tags.is_empty());
 /// ```
@@ -642,6 +3022,23 @@ pub fn tags_from_interpreter(interpreter: &str) -> TagSet {
     // Extract the interpreter name from the path
     let interpreter_name = interpreter.split('/').next_back().unwrap_or(interpreter);
 
+    let tags = interpreter_tags_stripping_dots(interpreter_name);
+    if !tags.is_empty() {
+        return tags;
+    }
+
+    // Strip a trailing version suffix (e.g., "bash5" -> "bash", "zsh-5.8" -> "zsh")
+    let stripped = interpreter_name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    let stripped = stripped.strip_suffix('-').unwrap_or(stripped);
+    if stripped.is_empty() || stripped == interpreter_name {
+        return TagSet::new();
+    }
+
+    get_interpreter_tags(stripped)
+}
+
+/// Look up an interpreter, trying progressively shorter dot-separated prefixes.
+fn interpreter_tags_stripping_dots(interpreter_name: &str) -> TagSet {
     // Try progressively shorter versions (e.g., "python3.5.2" -> "python3.5" -> "python3")
     let mut current = interpreter_name;
     while !current.is_empty() {
@@ -675,7 +3072,7 @@ pub fn tags_from_interpreter(interpreter: &str) -> TagSet {
 ///
 /// # Errors
 ///
-/// Returns an error if the file cannot be opened or read.
+/// Returns [`IdentifyError::IoErrorAt`] if the file cannot be opened or read.
 ///
 /// # Examples
 ///
@@ -694,8 +3091,11 @@ pub fn tags_from_interpreter(interpreter: &str) -> TagSet {
 /// assert!(!file_is_text(&binary_path).unwrap());
 /// ```
 pub fn file_is_text<P: AsRef<Path>>(path: P) -> Result<bool> {
-    let file = fs::File::open(path)?;
-    is_text(file)
+    let path = path.as_ref();
+    fs::File::open(path)
+        .map_err(IdentifyError::from)
+        .and_then(is_text)
+        .map_err(|e| e.at_path(path))
 }
 
 /// Determine if data from a reader contains text or binary content.
@@ -723,24 +3123,61 @@ pub fn file_is_text<P: AsRef<Path>>(path: P) -> Result<bool> {
 /// let binary_data = Cursor::new(&[0x7f, 0x45, 0x4c, 0x46, 0x00]);
 /// assert!(!is_text(binary_data).unwrap());
 /// ```
-pub fn is_text<R: Read>(mut reader: R) -> Result<bool> {
-    let mut buffer = [0; 1024];
-    let bytes_read = reader.read(&mut buffer)?;
-
-    // Check for null bytes or other non-text indicators
-    let text_chars: HashSet<u8> = [
-        7, 8, 9, 10, 11, 12, 13, 27, // Control chars
-    ]
-    .iter()
-    .cloned()
-    .chain(0x20..0x7F) // ASCII printable
-    .chain(0x80..=0xFF) // Extended ASCII
-    .collect();
-
-    let is_text = buffer[..bytes_read]
+pub fn is_text<R: Read>(reader: R) -> Result<bool> {
+    is_text_with_limit(reader, CONTENT_SAMPLE_SIZE)
+}
+
+/// Determine if data from a reader contains text, sampling up to `limit` bytes.
+///
+/// Like [`is_text`], but with a configurable sample size: a larger limit
+/// catches binary content further into the data.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::is_text_with_limit;
+/// use std::io::Cursor;
+///
+/// let mut data = vec![b'a'; 2048];
+/// data.push(0);
+/// assert!(is_text_with_limit(Cursor::new(&data), 1024).unwrap());
+/// assert!(!is_text_with_limit(Cursor::new(&data), 4096).unwrap());
+/// ```
+pub fn is_text_with_limit<R: Read>(reader: R, limit: usize) -> Result<bool> {
+    let mut buffer = Vec::with_capacity(limit.min(CONTENT_SAMPLE_SIZE * 64));
+    reader.take(limit as u64).read_to_end(&mut buffer)?;
+    Ok(bytes_are_text(&buffer))
+}
+
+/// Classify a prefix of streamed content as text or binary.
+///
+/// Uses the same rules as [`is_text`], but on a prefix that may be followed
+/// by more data: once a non-text byte (such as NUL) is seen the content is
+/// binary regardless of what follows, so streaming callers can decide early
+/// on [`TextVerdict::DefinitelyBinary`].
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::{TextVerdict, classify_text_partial};
+///
+/// assert_eq!(classify_text_partial(b"fn main() {"), TextVerdict::LikelyText);
+/// assert_eq!(classify_text_partial(b"\x7fELF\x02\x01"), TextVerdict::DefinitelyBinary);
+/// ```
+pub fn classify_text_partial(bytes: &[u8]) -> TextVerdict {
+    if bytes_are_text(bytes) {
+        TextVerdict::LikelyText
+    } else {
+        TextVerdict::DefinitelyBinary
+    }
+}
+
+/// Check whether bytes contain only text characters: printable ASCII, common
+/// control characters and any high bytes.
+pub(crate) fn bytes_are_text(bytes: &[u8]) -> bool {
+    bytes
         .iter()
-        .all(|&byte| text_chars.contains(&byte));
-    Ok(is_text)
+        .all(|&byte| matches!(byte, 7..=13 | 27 | 0x20..=0x7e | 0x80..=0xff))
 }
 
 /// Parse shebang line from an executable file and return raw shebang components.
@@ -761,7 +3198,7 @@ pub fn is_text<R: Read>(mut reader: R) -> Result<bool> {
 ///
 /// # Errors
 ///
-/// Returns an error if the file cannot be accessed or read.
+/// Returns [`IdentifyError::IoErrorAt`] if the file cannot be accessed or read.
 ///
 /// # Examples
 ///
@@ -784,7 +3221,7 @@ pub fn parse_shebang_from_file<P: AsRef<Path>>(path: P) -> Result<ShebangTuple>
     let path = path.as_ref();
 
     // Only check executable files
-    let metadata = fs::metadata(path)?;
+    let metadata = fs::metadata(path).map_err(|e| IdentifyError::from(e).at_path(path))?;
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -793,8 +3230,10 @@ pub fn parse_shebang_from_file<P: AsRef<Path>>(path: P) -> Result<ShebangTuple>
         }
     }
 
-    let file = fs::File::open(path)?;
-    parse_shebang(file)
+    fs::File::open(path)
+        .map_err(IdentifyError::from)
+        .and_then(parse_shebang)
+        .map_err(|e| e.at_path(path))
 }
 
 /// Parse a shebang line from a reader and return raw shebang components.
@@ -811,6 +3250,12 @@ pub fn parse_shebang_from_file<P: AsRef<Path>>(path: P) -> Result<ShebangTuple>
 ///
 /// A vector of raw shebang components. Returns an empty vector if no valid shebang is found.
 ///
+/// This parses any `#!` line regardless of whether the file it came from is
+/// executable, so a non-executable document that happens to start with `#!`
+/// still yields components. Identification only consults the shebang of
+/// executable files; use [`parse_shebang_if_executable`] when executability
+/// is known to apply the same rule.
+///
 /// # Examples
 ///
 /// ```rust
@@ -826,6 +3271,108 @@ pub fn parse_shebang_from_file<P: AsRef<Path>>(path: P) -> Result<ShebangTuple>
 /// assert!(components.is_empty());
 /// ```
 pub fn parse_shebang<R: Read>(reader: R) -> Result<ShebangTuple> {
+    parse_shebang_detailed(reader).map(|info| info.components)
+}
+
+/// Parse a shebang line from a reader, but only for executable files.
+///
+/// A `#!` line only selects an interpreter when the file is executed, so
+/// for non-executable files this returns an empty [`ShebangTuple`] without
+/// reading from `reader`. Executable files are parsed as by
+/// [`parse_shebang`].
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::parse_shebang_if_executable;
+/// use std::io::Cursor;
+///
+/// let content = b"#!/bin/bash\necho hi\n";
+/// let components = parse_shebang_if_executable(true, Cursor::new(content)).unwrap();
+/// assert_eq!(components.get(0).unwrap(), "/bin/bash");
+///
+/// let components = parse_shebang_if_executable(false, Cursor::new(content)).unwrap();
+/// assert!(components.is_empty());
+/// ```
+pub fn parse_shebang_if_executable<R: Read>(
+    is_executable: bool,
+    reader: R,
+) -> Result<ShebangTuple> {
+    if !is_executable {
+        return Ok(ShebangTuple::new());
+    }
+    parse_shebang(reader)
+}
+
+/// Details of a parsed shebang line, as returned by [`parse_shebang_detailed`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShebangInfo {
+    /// Whether the interpreter is invoked through `/usr/bin/env`.
+    pub via_env: bool,
+    /// The interpreter's path when the shebang names it by absolute path,
+    /// e.g. `/usr/bin/python` for `#!/usr/bin/python`. `None` when the
+    /// interpreter is looked up on `PATH` through `env`, or there is no shebang.
+    pub interpreter_path: Option<String>,
+    /// The command components, exactly as returned by [`parse_shebang`].
+    pub components: ShebangTuple,
+}
+
+/// Parse a shebang line from a reader, keeping how the interpreter was invoked.
+///
+/// Returns the same components as [`parse_shebang`], along with whether
+/// `/usr/bin/env` was used and the interpreter's absolute path if given.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::parse_shebang_detailed;
+/// use std::io::Cursor;
+///
+/// let info = parse_shebang_detailed(Cursor::new(b"#!/usr/bin/env python3")).unwrap();
+/// assert!(info.via_env);
+/// assert_eq!(info.interpreter_path, None);
+/// assert_eq!(info.components.get(0).unwrap(), "python3");
+///
+/// let info = parse_shebang_detailed(Cursor::new(b"#!/usr/bin/python3 -u")).unwrap();
+/// assert!(!info.via_env);
+/// assert_eq!(info.interpreter_path.as_deref(), Some("/usr/bin/python3"));
+/// ```
+pub fn parse_shebang_detailed<R: Read>(reader: R) -> Result<ShebangInfo> {
+    parse_shebang_line(reader, false)
+}
+
+/// Parse a shebang line, reporting a `#!` line that is not valid UTF-8.
+///
+/// [`parse_shebang`] returns an empty tuple both when there is no shebang
+/// and when the shebang line is not valid UTF-8. This variant tells the two
+/// apart for diagnostics; all other input is parsed as by [`parse_shebang`].
+///
+/// # Errors
+///
+/// Returns [`IdentifyError::InvalidUtf8`] if the first line starts with `#!`
+/// but is not valid UTF-8.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::{IdentifyError, parse_shebang_strict};
+/// use std::io::Cursor;
+///
+/// let components = parse_shebang_strict(Cursor::new(b"#!/bin/sh\n")).unwrap();
+/// assert_eq!(components.get(0).unwrap(), "/bin/sh");
+///
+/// let err = parse_shebang_strict(Cursor::new(b"#!\xff\xfe\n")).unwrap_err();
+/// assert!(matches!(err, IdentifyError::InvalidUtf8));
+/// ```
+pub fn parse_shebang_strict<R: Read>(reader: R) -> Result<ShebangTuple> {
+    parse_shebang_line(reader, true).map(|info| info.components)
+}
+
+/// Parse the first line of `reader` as a shebang.
+///
+/// With `strict`, a `#!` line that is not valid UTF-8 is an error instead of
+/// being treated as no shebang.
+fn parse_shebang_line<R: Read>(reader: R, strict: bool) -> Result<ShebangInfo> {
     use std::io::BufRead;
 
     let mut buf_reader = BufReader::new(reader);
@@ -833,7 +3380,7 @@ pub fn parse_shebang<R: Read>(reader: R) -> Result<ShebangTuple> {
     // Read first line efficiently using read_until
     let mut first_line_bytes = Vec::new();
     match buf_reader.read_until(b'\n', &mut first_line_bytes) {
-        Ok(0) => return Ok(ShebangTuple::new()), // EOF with no data
+        Ok(0) => return Ok(ShebangInfo::default()), // EOF with no data
         Ok(_) => {
             // Remove trailing newline if present
             if first_line_bytes.ends_with(b"\n") {
@@ -844,72 +3391,176 @@ pub fn parse_shebang<R: Read>(reader: R) -> Result<ShebangTuple> {
                 first_line_bytes.pop();
             }
         }
-        Err(_) => return Ok(ShebangTuple::new()), // Read error
+        Err(_) => return Ok(ShebangInfo::default()), // Read error
     }
 
     // Check if starts with shebang
     if first_line_bytes.len() < 2 || &first_line_bytes[0..2] != b"#!" {
-        return Ok(ShebangTuple::new());
+        return Ok(ShebangInfo::default());
     }
 
     // Limit line length to prevent memory issues
-    if first_line_bytes.len() > 1024 {
+    let truncated = first_line_bytes.len() > 1024;
+    if truncated {
         first_line_bytes.truncate(1024);
     }
 
-    // Try to decode as UTF-8, return empty if invalid (like Python does)
+    // Try to decode as UTF-8, return empty if invalid (like Python does).
+    // A character cut off by the length limit is not reported as invalid.
     let first_line = match String::from_utf8(first_line_bytes) {
         Ok(line) => line,
-        Err(_) => return Ok(ShebangTuple::new()),
+        Err(e) if strict && (e.utf8_error().error_len().is_some() || !truncated) => {
+            return Err(IdentifyError::InvalidUtf8);
+        }
+        Err(_) => return Ok(ShebangInfo::default()),
     };
 
-    // Remove the #! and clean up the line
-    let shebang_line = first_line[2..].trim();
+    // Remove the #! and parse the rest
+    Ok(parse_shebang_command(&first_line[2..]))
+}
+
+/// Parse the text following `#!` into shebang details.
+fn parse_shebang_command(line: &str) -> ShebangInfo {
+    let shebang_line = line.trim();
 
     // Check for only printable ASCII (like Python does)
     for c in shebang_line.chars() {
         if !c.is_ascii() || (c.is_control() && c != '\t') {
-            return Ok(ShebangTuple::new());
+            return ShebangInfo::default();
         }
     }
 
     // Parse the shebang command using simple split (like Python's shlex fallback)
     let parts: smallvec::SmallVec<[&str; 4]> = shebang_line.split_whitespace().collect();
     if parts.is_empty() {
-        return Ok(ShebangTuple::new());
+        return ShebangInfo::default();
     }
 
-    let cmd: smallvec::SmallVec<[&str; 2]> = if parts[0] == "/usr/bin/env" {
-        if parts.len() == 1 {
-            // Just "#!/usr/bin/env" with no interpreter
-            smallvec::SmallVec::new()
-        } else if parts.len() >= 2 && parts[1] == "-S" {
-            if parts.len() > 2 {
-                parts[2..].iter().copied().collect()
-            } else {
-                // Just "#!/usr/bin/env -S" with no interpreter
-                smallvec::SmallVec::new()
-            }
-        } else {
-            parts[1..].iter().copied().collect()
-        }
+    // Repeated leading slashes name the same path, as in `#!//usr/bin/env`
+    let via_env = parts[0].starts_with('/') && parts[0].trim_start_matches('/') == "usr/bin/env";
+    let cmd: smallvec::SmallVec<[&str; 2]> = if via_env {
+        // An empty result covers "#!/usr/bin/env" and "#!/usr/bin/env -S" with no interpreter
+        strip_env_options(&parts[1..])
     } else {
         parts.iter().copied().collect()
     };
 
     if cmd.is_empty() {
-        return Ok(ShebangTuple::new());
+        return ShebangInfo::default();
     }
 
+    let interpreter_path = cmd[0].starts_with('/').then(|| cmd[0].to_string());
+
     // Return the raw command components as strings
-    Ok(ShebangTuple::from_vec(
-        cmd.iter().map(|s| s.to_string()).collect(),
-    ))
+    ShebangInfo {
+        via_env,
+        interpreter_path,
+        components: ShebangTuple::from_vec(cmd.iter().map(|s| s.to_string()).collect()),
+    }
+}
+
+/// Get the Node module type of a `.js` file from the nearest `package.json`.
+///
+/// Returns `module` or `commonjs`, or `None` if no `package.json` is found or
+/// the closest one is not valid JSON.
+fn node_module_type(path: &Path) -> Option<&'static str> {
+    let contents = path
+        .parent()?
+        .ancestors()
+        .find_map(|dir| fs::read(dir.join("package.json")).ok())?;
+    let package: serde_json::Value = serde_json::from_slice(&contents).ok()?;
+    match package.get("type").and_then(|t| t.as_str()) {
+        Some("module") => Some("module"),
+        _ => Some("commonjs"),
+    }
+}
+
+/// Find the interpreter a `-c` shell command hands the script to with `exec`.
+///
+/// `args` are the shebang arguments after the interpreter. The command is
+/// split on whitespace, ignoring quotes, so `-c 'exec python3 "$0" "$@"'`
+/// yields `python3`.
+fn polyglot_exec_target(args: &[String]) -> Option<&str> {
+    let command = args.get(args.iter().position(|arg| arg == "-c")? + 1..)?;
+    let mut words = command
+        .iter()
+        .map(|word| word.trim_matches(|c| c == '\'' || c == '"'))
+        .filter(|word| !word.is_empty());
+    words.find(|&word| word == "exec")?;
+    let target = words.next()?;
+    Some(target.rsplit('/').next().unwrap_or(target))
+}
+
+/// Skip `env` options and variable assignments, returning the command that follows.
+///
+/// Handles combined short options such as `-vS`, options taking a separate
+/// argument (`-u NAME`, `-C DIR`), `--` and `NAME=VALUE` assignments. Text
+/// attached after `S` (as in `-Spython3`) is treated as the first command word.
+fn strip_env_options<'a>(args: &[&'a str]) -> smallvec::SmallVec<[&'a str; 2]> {
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i];
+
+        if arg == "--" {
+            i += 1;
+            break;
+        }
+
+        if let Some(long) = arg.strip_prefix("--") {
+            if let Some(value) = long.strip_prefix("split-string=") {
+                return std::iter::once(value)
+                    .filter(|value| !value.is_empty())
+                    .chain(args[i + 1..].iter().copied())
+                    .collect();
+            }
+            i += if matches!(long, "unset" | "chdir") {
+                2
+            } else {
+                1
+            };
+            continue;
+        }
+
+        if let Some(cluster) = arg.strip_prefix('-').filter(|c| !c.is_empty()) {
+            let mut skip = 1;
+            for (pos, c) in cluster.char_indices() {
+                let attached = &cluster[pos + c.len_utf8()..];
+                match c {
+                    'S' => {
+                        return std::iter::once(attached)
+                            .filter(|attached| !attached.is_empty())
+                            .chain(args[i + 1..].iter().copied())
+                            .collect();
+                    }
+                    'u' | 'C' => {
+                        // The option argument is attached or is the next word
+                        if attached.is_empty() {
+                            skip = 2;
+                        }
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            i += skip;
+            continue;
+        }
+
+        if arg.contains('=') {
+            i += 1;
+            continue;
+        }
+
+        break;
+    }
+
+    args.get(i..).unwrap_or_default().iter().copied().collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
     use std::fs;
     use std::io::Cursor;
     use std::os::unix::fs::PermissionsExt;
@@ -925,6 +3576,62 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_shebang_tuple_accessors() {
+        let empty = shebang_tuple!();
+        assert_eq!(empty.interpreter(), None);
+        assert!(empty.args().is_empty());
+        assert_eq!(empty.join(" "), "");
+
+        let single = shebang_tuple!["python3"];
+        assert_eq!(single.interpreter(), Some("python3"));
+        assert!(single.args().is_empty());
+        assert_eq!(single.join(" "), "python3");
+
+        let with_args = shebang_tuple!["python3", "-u"];
+        assert_eq!(with_args.interpreter(), Some("python3"));
+        assert_eq!(with_args.args(), ["-u"]);
+        assert_eq!(with_args.join(" "), "python3 -u");
+    }
+
+    #[test]
+    fn test_shebang_tuple_into_iter_owned() {
+        let tuple = shebang_tuple!["python3", "-E", "-s"];
+        let borrowed: Vec<&String> = (&tuple).into_iter().collect();
+        assert_eq!(borrowed, ["python3", "-E", "-s"]);
+
+        let owned: Vec<String> = tuple.into_iter().collect();
+        assert_eq!(owned, ["python3", "-E", "-s"]);
+
+        let args: Vec<String> = shebang_tuple!["bash", "-eu"].into_iter().skip(1).collect();
+        assert_eq!(args, ["-eu"]);
+    }
+
+    #[test]
+    fn test_shebang_tuple_parse_line() {
+        assert_eq!(
+            ShebangTuple::parse_line("/usr/bin/env python3"),
+            shebang_tuple!["python3"]
+        );
+        assert_eq!(
+            ShebangTuple::parse_line("  /bin/bash -eu  "),
+            shebang_tuple!["/bin/bash", "-eu"]
+        );
+        assert_eq!(
+            ShebangTuple::parse_line("/usr/bin/env -S deno run"),
+            shebang_tuple!["deno", "run"]
+        );
+        assert!(ShebangTuple::parse_line("/usr/bin/env").is_empty());
+        assert!(ShebangTuple::parse_line("/usr/bin/pythön").is_empty());
+
+        // Agrees with parsing the full line from a reader
+        let line = "/usr/bin/env -i PATH=/bin ruby -w";
+        assert_eq!(
+            ShebangTuple::parse_line(line),
+            parse_shebang(Cursor::new(format!("#!{line}\n"))).unwrap()
+        );
+    }
+
     // Test tag system completeness
     #[test]
     fn test_all_basic_tags_exist() {
@@ -941,6 +3648,93 @@ mod tests {
         assert!(MODE_TAGS.is_disjoint(&ENCODING_TAGS));
     }
 
+    #[test]
+    fn test_tag_enum_round_trip() {
+        for tag in Tag::ALL {
+            assert_eq!(tag.as_str().parse::<Tag>(), Ok(tag));
+            assert_eq!(tag.to_string(), tag.as_str());
+            assert!(tag == *tag.as_str());
+        }
+        assert_eq!(
+            "python".parse::<Tag>(),
+            Err(ParseTagError("python".to_string()))
+        );
+
+        assert!(is_type_tag(Tag::Fifo));
+        assert!(is_mode_tag(Tag::NonExecutable));
+        assert!(is_encoding_tag(Tag::Binary));
+        assert!(!is_encoding_tag(Tag::File));
+        assert_eq!(
+            Tag::ALL.iter().filter(|tag| is_type_tag(tag)).count(),
+            TYPE_TAGS.len()
+        );
+    }
+
+    #[test]
+    fn test_contains_any_and_all() {
+        let tags = tags_from_filename("config.yaml");
+        assert!(contains_any(&tags, &["json", "yaml"]));
+        assert!(!contains_any(&tags, &["json", "toml"]));
+        assert!(!contains_any(&tags, &[]));
+
+        assert!(contains_all(&tags, &["text", "yaml"]));
+        assert!(!contains_all(&tags, &["text", "json"]));
+        assert!(contains_all(&tags, &[]));
+    }
+
+    #[test]
+    fn test_validate_tags() {
+        assert_eq!(validate(&tags_from_filename("script.py")), Ok(()));
+        assert_eq!(
+            validate(&HashSet::from(["file", "executable", "text", "python"])),
+            Ok(())
+        );
+        assert_eq!(validate(&TagSet::new()), Ok(()));
+
+        assert_eq!(
+            validate(&HashSet::from(["file", "text", "binary"])),
+            Err(TagInconsistency::MultipleEncodings(vec!["binary", "text"]))
+        );
+        assert_eq!(
+            validate(&HashSet::from(["executable", "non-executable"])),
+            Err(TagInconsistency::MultipleModes(vec![
+                "executable",
+                "non-executable"
+            ]))
+        );
+        assert_eq!(
+            validate(&HashSet::from(["file", "directory", "text", "binary"])),
+            Err(TagInconsistency::MultipleTypes(vec!["directory", "file"]))
+        );
+    }
+
+    #[test]
+    fn test_primary_language() {
+        let tags = HashSet::from(["file", "executable", "text", "python", "python3"]);
+        assert_eq!(primary_language(&tags), Some("python"));
+
+        let tags = HashSet::from(["file", "executable", "text", "bash", "shell"]);
+        assert_eq!(primary_language(&tags), Some("bash"));
+        assert_eq!(primary_language(&tags_from_interpreter("sh")), Some("sh"));
+
+        assert_eq!(
+            primary_language(&tags_from_filename("App.tsx")),
+            Some("tsx")
+        );
+        assert_eq!(
+            primary_language(&tags_from_filename("view.mm")),
+            Some("objective-c++")
+        );
+        assert_eq!(
+            primary_language(&tags_from_filename("Cargo.toml")),
+            Some("toml")
+        );
+
+        let tags = HashSet::from(["file", "non-executable", "text", "plain-text"]);
+        assert_eq!(primary_language(&tags), None);
+        assert_eq!(primary_language(&TagSet::new()), None);
+    }
+
     // Test tags_from_filename with various scenarios
     #[test]
     fn test_tags_from_filename_basic() {
@@ -984,11 +3778,169 @@ mod tests {
         let tags = tags_from_filename("Dockerfile.xenial");
         assert!(tags.contains("dockerfile"));
 
+        // Podman's Containerfile is a Dockerfile synonym, suffixes included
+        for name in ["Containerfile", "Containerfile.dev"] {
+            assert_eq!(
+                tags_from_filename(name),
+                HashSet::from(["text", "dockerfile"])
+            );
+        }
+
         let tags = tags_from_filename("README.md");
         assert!(tags.contains("markdown"));
         assert!(tags.contains("plain-text"));
     }
 
+    #[test]
+    fn test_tags_from_filename_requirements_txt() {
+        for name in [
+            "requirements.txt",
+            "requirements-dev.txt",
+            "requirements_test.txt",
+            "dev-requirements.txt",
+            "constraints.txt",
+            "deps/requirements.txt",
+        ] {
+            let tags = tags_from_filename(name);
+            assert!(tags.contains("requirements-txt"), "{name}: {tags:?}");
+            assert!(tags.contains("text"), "{name}: {tags:?}");
+        }
+
+        assert!(!tags_from_filename("notes.txt").contains("requirements-txt"));
+        assert!(!tags_from_filename("requirements.in").contains("requirements-txt"));
+        for name in [
+            "requirementsfoo.txt",
+            "my_constraints.txt",
+            "myrequirements.txt",
+        ] {
+            assert!(
+                !tags_from_filename(name).contains("requirements-txt"),
+                "{name}"
+            );
+        }
+        assert!(tags_from_filename("requirements.dev.txt").contains("requirements-txt"));
+        assert!(tags_from_filename("dev-constraints.txt").contains("requirements-txt"));
+        assert!(tags_from_filename("requirements.TXT").contains("requirements-txt"));
+    }
+
+    #[test]
+    fn test_tags_from_filename_shared_libraries() {
+        for name in [
+            "libc.so.6",
+            "libfoo.so.1.2.3",
+            "libfoo.so",
+            "foo.dll",
+            "libfoo.dylib",
+            "libfoo.1.dylib",
+            "/usr/lib/libssl.so.3",
+        ] {
+            let tags = tags_from_filename(name);
+            assert_eq!(tags, HashSet::from(["binary", "shared-library"]), "{name}");
+        }
+
+        assert!(tags_from_filename("archive.tar.1").is_empty());
+        assert!(tags_from_filename("so.6").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_tags_from_os_filename_invalid_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = OsStr::from_bytes(b"caf\xe9.py");
+        assert!(name.to_str().is_none());
+        assert_eq!(tags_from_os_filename(name), tags_from_filename("cafe.py"));
+        assert_eq!(
+            tags_from_os_filename(OsStr::from_bytes(b"Dockerfile.\xff")),
+            tags_from_filename("Dockerfile")
+        );
+        assert!(tags_from_os_filename(OsStr::from_bytes(b"data.p\xffy")).is_empty());
+        assert!(tags_from_os_filename(OsStr::from_bytes(b".\xffpy")).is_empty());
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, "print('hello')\n").unwrap();
+        let tags = tags_from_path(&path).unwrap();
+        assert!(tags.contains("python"));
+        assert!(tags.contains("text"));
+        assert_eq!(FileIdentifier::new().identify(&path).unwrap(), tags);
+
+        let custom = FileIdentifier::new().with_custom_extensions(std::collections::HashMap::from(
+            [("py".to_string(), HashSet::from(["text", "custom"]))],
+        ));
+        assert!(custom.identify(&path).unwrap().contains("custom"));
+    }
+
+    #[test]
+    fn test_tags_from_filename_with_path_ci_conventions() {
+        let cases = [
+            (".github/workflows/ci.yml", "github-workflow"),
+            ("/repo/.github/workflows/release.yaml", "github-workflow"),
+            (".gitlab-ci.yml", "gitlab-ci"),
+            ("/repo/.gitlab-ci.yml", "gitlab-ci"),
+            (".circleci/config.yml", "circleci"),
+            ("/repo/.circleci/config.yml", "circleci"),
+            ("azure-pipelines.yml", "azure-pipelines"),
+            ("ci/azure-pipelines.yaml", "azure-pipelines"),
+        ];
+        for (path, ci_tag) in cases {
+            let tags = tags_from_filename_with_path(path);
+            assert!(tags.contains(ci_tag), "{path}: {tags:?}");
+            assert!(tags.contains("yaml"), "{path}: {tags:?}");
+        }
+
+        // Conventions require the right directories
+        for path in [
+            "workflows/ci.yml",
+            ".github/ci.yml",
+            ".github/workflows/nested/ci.yml",
+            "config.yml",
+            ".circleci/other.yml",
+            ".github/workflows/README.md",
+        ] {
+            let tags = tags_from_filename_with_path(path);
+            assert!(
+                !tags
+                    .iter()
+                    .any(|t| ["github-workflow", "circleci"].contains(t)),
+                "{path}: {tags:?}"
+            );
+        }
+
+        // The basename alone is not enough
+        assert!(!tags_from_filename("ci.yml").contains("github-workflow"));
+    }
+
+    #[test]
+    fn test_tags_from_path_ci_conventions() {
+        let dir = tempdir().unwrap();
+        let workflows = dir.path().join(".github").join("workflows");
+        fs::create_dir_all(&workflows).unwrap();
+        let workflow = workflows.join("ci.yml");
+        fs::write(&workflow, "on: push\n").unwrap();
+
+        let tags = tags_from_path(&workflow).unwrap();
+        assert!(tags.contains("github-workflow"));
+        assert!(tags.contains("yaml"));
+
+        let tags = FileIdentifier::new().identify(&workflow).unwrap();
+        assert!(tags.contains("github-workflow"));
+    }
+
+    #[test]
+    fn test_tags_from_filenames() {
+        let results = tags_from_filenames(&["a.py", "b.js", "weird.xyz"]);
+        assert_eq!(
+            results,
+            vec![
+                HashSet::from(["text", "python"]),
+                HashSet::from(["text", "javascript"]),
+                HashSet::new(),
+            ]
+        );
+        assert!(tags_from_filenames(&[]).is_empty());
+    }
+
     #[test]
     fn test_tags_from_filename_unrecognized() {
         let tags = tags_from_filename("unknown.xyz");
@@ -1017,6 +3969,31 @@ mod tests {
         assert!(tags.contains("php8"));
     }
 
+    #[test]
+    fn test_tags_from_interpreter_numeric_suffix() {
+        let tags = tags_from_interpreter("bash5");
+        assert_eq!(tags, HashSet::from(["shell", "bash"]));
+
+        let tags = tags_from_interpreter("/usr/local/bin/zsh-5.8");
+        assert_eq!(tags, HashSet::from(["shell", "zsh"]));
+
+        // Known versioned interpreters keep their specific tags
+        let tags = tags_from_interpreter("python3");
+        assert_eq!(tags, HashSet::from(["python", "python3"]));
+
+        let tags = tags_from_interpreter("php8");
+        assert_eq!(tags, HashSet::from(["php", "php8"]));
+    }
+
+    #[test]
+    fn test_all_interpreter_names() {
+        let names = interpreters::all_interpreter_names();
+        assert!(names.contains(&"python3"));
+        assert!(names.contains(&"bash"));
+        assert!(names.contains(&"node"));
+        assert!(names.is_sorted());
+    }
+
     #[test]
     fn test_tags_from_interpreter_with_path() {
         let tags = tags_from_interpreter("/usr/bin/python3");
@@ -1056,6 +4033,20 @@ mod tests {
         assert!(!is_text(Cursor::new(&[0x43, 0x92, 0xd9, 0x0f, 0xaf, 0x32, 0x2c])).unwrap());
     }
 
+    #[test]
+    fn test_classify_text_partial() {
+        assert_eq!(
+            classify_text_partial(b"#!/bin/sh\necho hello\n"),
+            TextVerdict::LikelyText
+        );
+        assert_eq!(classify_text_partial(b""), TextVerdict::LikelyText);
+        // A NUL byte decides the verdict even with text on either side
+        assert_eq!(
+            classify_text_partial(b"hello\x00world"),
+            TextVerdict::DefinitelyBinary
+        );
+    }
+
     // Test parse_shebang function
     #[test]
     fn test_parse_shebang_basic() {
@@ -1066,12 +4057,75 @@ mod tests {
         assert_eq!(components, shebang_tuple!["python"]);
     }
 
+    #[test]
+    fn test_parse_shebang_detailed() {
+        let info = parse_shebang_detailed(Cursor::new(b"#!/usr/bin/python -u")).unwrap();
+        assert!(!info.via_env);
+        assert_eq!(info.interpreter_path.as_deref(), Some("/usr/bin/python"));
+        assert_eq!(info.components, shebang_tuple!["/usr/bin/python", "-u"]);
+
+        let info = parse_shebang_detailed(Cursor::new(b"#!/usr/bin/env -S python -u")).unwrap();
+        assert!(info.via_env);
+        assert_eq!(info.interpreter_path, None);
+        assert_eq!(info.components, shebang_tuple!["python", "-u"]);
+
+        let info =
+            parse_shebang_detailed(Cursor::new(b"#!/usr/bin/env /opt/py/bin/python3")).unwrap();
+        assert!(info.via_env);
+        assert_eq!(
+            info.interpreter_path.as_deref(),
+            Some("/opt/py/bin/python3")
+        );
+
+        let info = parse_shebang_detailed(Cursor::new(b"print('no shebang')")).unwrap();
+        assert_eq!(info, ShebangInfo::default());
+    }
+
+    #[test]
+    fn test_parse_shebang_env_double_slash() {
+        for shebang in [
+            &b"#!/usr/bin/env python3\n"[..],
+            b"#!//usr/bin/env python3\n",
+        ] {
+            let components = parse_shebang(Cursor::new(shebang)).unwrap();
+            assert_eq!(components, shebang_tuple!["python3"]);
+        }
+
+        let info = parse_shebang_detailed(Cursor::new(b"#!//usr/bin/env python3\n")).unwrap();
+        assert!(info.via_env);
+
+        // Only the env path itself is normalized
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/envoy python3\n")).unwrap();
+        assert_eq!(components, shebang_tuple!["/usr/bin/envoy", "python3"]);
+    }
+
     #[test]
     fn test_parse_shebang_env_with_flags() {
         let components = parse_shebang(Cursor::new(b"#!/usr/bin/env -S python -u")).unwrap();
         assert_eq!(components, shebang_tuple!["python", "-u"]);
     }
 
+    #[test]
+    fn test_parse_shebang_env_combined_options() {
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/env -vS python3 -u")).unwrap();
+        assert_eq!(components, shebang_tuple!["python3", "-u"]);
+
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/env -S python3")).unwrap();
+        assert_eq!(components, shebang_tuple!["python3"]);
+
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/env -Spython3 -u")).unwrap();
+        assert_eq!(components, shebang_tuple!["python3", "-u"]);
+
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/env -i -u HOME python3")).unwrap();
+        assert_eq!(components, shebang_tuple!["python3"]);
+
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/env LANG=C -- perl -w")).unwrap();
+        assert_eq!(components, shebang_tuple!["perl", "-w"]);
+
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/env -vS")).unwrap();
+        assert!(components.is_empty());
+    }
+
     #[test]
     fn test_parse_shebang_spaces() {
         let components = parse_shebang(Cursor::new(b"#! /usr/bin/python")).unwrap();
@@ -1081,6 +4135,23 @@ mod tests {
         assert_eq!(components, shebang_tuple!["/usr/bin/foo", "python"]);
     }
 
+    #[test]
+    fn test_parse_shebang_interpreter_options() {
+        let components = parse_shebang(Cursor::new(b"#!/usr/bin/python3 -E -s\n")).unwrap();
+        assert_eq!(components, shebang_tuple!["/usr/bin/python3", "-E", "-s"]);
+        assert_eq!(components.interpreter(), Some("/usr/bin/python3"));
+        assert_eq!(components.args(), ["-E", "-s"]);
+
+        let tags = tags_from_interpreter(components.interpreter().unwrap());
+        assert_eq!(tags, HashSet::from(["python", "python3"]));
+
+        let tags = FileIdentifier::new()
+            .identify_parts("tool", true, Cursor::new(b"#!/usr/bin/python3 -E -s\n"))
+            .unwrap();
+        assert!(tags.contains("python"));
+        assert!(tags.contains("python3"));
+    }
+
     #[test]
     fn test_parse_shebang_no_shebang() {
         let components = parse_shebang(Cursor::new(b"import sys")).unwrap();
@@ -1090,16 +4161,109 @@ mod tests {
         assert!(components.is_empty());
     }
 
+    #[test]
+    fn test_parse_shebang_if_executable() {
+        struct Unread;
+        impl Read for Unread {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                panic!("non-executable content must not be read");
+            }
+        }
+
+        let components = parse_shebang_if_executable(false, Unread).unwrap();
+        assert!(components.is_empty());
+
+        let content = b"#!/usr/bin/env python3\nprint('hi')\n";
+        let components = parse_shebang_if_executable(true, Cursor::new(content)).unwrap();
+        assert_eq!(components, shebang_tuple!["python3"]);
+
+        // A non-executable document starting with `#!` is never a script
+        let tags = FileIdentifier::new()
+            .identify_parts("NOTES", false, Cursor::new(content))
+            .unwrap();
+        assert!(!tags.contains("python"));
+        assert!(!tags.contains("python3"));
+    }
+
     #[test]
     fn test_parse_shebang_invalid_utf8() {
         let result = parse_shebang(Cursor::new(&[0x23, 0x21, 0xf9, 0x93, 0x01, 0x42, 0xcd]));
-        match result {
-            Ok(components) => assert!(components.is_empty()),
-            Err(_) => (), // I/O errors are acceptable for invalid UTF-8 data
+        // I/O errors are acceptable for invalid UTF-8 data
+        if let Ok(components) = result {
+            assert!(components.is_empty());
         }
     }
 
+    #[test]
+    fn test_parse_shebang_strict() {
+        let err = parse_shebang_strict(Cursor::new(b"#!\xff\xfe")).unwrap_err();
+        assert!(matches!(err, IdentifyError::InvalidUtf8));
+
+        // The lenient parser cannot tell this apart from no shebang
+        assert!(
+            parse_shebang(Cursor::new(b"#!\xff\xfe"))
+                .unwrap()
+                .is_empty()
+        );
+
+        let components = parse_shebang_strict(Cursor::new(b"#!/usr/bin/env python3\n")).unwrap();
+        assert_eq!(components, shebang_tuple!["python3"]);
+
+        // Invalid UTF-8 outside a shebang line is not an error
+        assert!(
+            parse_shebang_strict(Cursor::new(b"\xff\xfe"))
+                .unwrap()
+                .is_empty()
+        );
+        let components = parse_shebang_strict(Cursor::new(b"#!/bin/sh\n\xff\xfe")).unwrap();
+        assert_eq!(components, shebang_tuple!["/bin/sh"]);
+    }
+
     // File system tests using tempfiles
+    #[test]
+    fn test_is_executable() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        let notes = dir.path().join("notes.txt");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::write(&notes, "hello").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(&notes, fs::Permissions::from_mode(0o644)).unwrap();
+
+        assert!(is_executable(&script).unwrap());
+        assert!(!is_executable(&notes).unwrap());
+
+        let result = is_executable(dir.path().join("missing"));
+        assert!(matches!(result, Err(IdentifyError::PathNotFound { .. })));
+    }
+
+    #[test]
+    fn test_tags_from_metadata() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.py");
+        let notes = dir.path().join("notes.txt");
+        fs::write(&script, "#!/usr/bin/env python3\n").unwrap();
+        fs::write(&notes, "hello").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(&notes, fs::Permissions::from_mode(0o644)).unwrap();
+
+        assert_eq!(
+            tags_from_metadata(dir.path()).unwrap(),
+            HashSet::from(["directory"])
+        );
+        assert_eq!(
+            tags_from_metadata(&script).unwrap(),
+            HashSet::from(["file", "executable"])
+        );
+        assert_eq!(
+            tags_from_metadata(&notes).unwrap(),
+            HashSet::from(["file", "non-executable"])
+        );
+
+        let result = tags_from_metadata(dir.path().join("missing"));
+        assert!(matches!(result, Err(IdentifyError::PathNotFound { .. })));
+    }
+
     #[test]
     fn test_tags_from_path_file_not_found() {
         let result = tags_from_path("/nonexistent/path");
@@ -1135,6 +4299,69 @@ mod tests {
         assert!(tags.contains("text"));
     }
 
+    #[test]
+    fn test_tags_from_path_sorted() {
+        let dir = tempdir().unwrap();
+        let script_path = dir.path().join("script.py");
+        fs::write(&script_path, "print('hello')").unwrap();
+        fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let tags = tags_from_path_sorted(&script_path).unwrap();
+        assert_eq!(tags, vec!["executable", "file", "python", "text"]);
+        assert!(tags.is_sorted());
+
+        assert_eq!(
+            tags_from_filename_sorted("Dockerfile"),
+            vec!["dockerfile", "text"]
+        );
+    }
+
+    #[test]
+    fn test_path_has_tag() {
+        let dir = tempdir().unwrap();
+
+        // Format tags are answered from the filename
+        let script_path = dir.path().join("script.py");
+        fs::write(&script_path, "print('hello')").unwrap();
+        assert!(path_has_tag(&script_path, "python").unwrap());
+        assert!(path_has_tag(&script_path, "file").unwrap());
+        assert!(path_has_tag(&script_path, "non-executable").unwrap());
+        assert!(!path_has_tag(&script_path, "executable").unwrap());
+        assert!(!path_has_tag(&script_path, "ruby").unwrap());
+
+        // ... or the shebang of an executable
+        let tool_path = dir.path().join("tool");
+        fs::write(&tool_path, "#!/usr/bin/env python3\nprint('hello')\n").unwrap();
+        fs::set_permissions(&tool_path, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(path_has_tag(&tool_path, "python").unwrap());
+        assert!(path_has_tag(&tool_path, "executable").unwrap());
+
+        // Encoding tags of unrecognized names need the content
+        let text_path = dir.path().join("notes");
+        fs::write(&text_path, "hello\n").unwrap();
+        assert!(path_has_tag(&text_path, "text").unwrap());
+        assert!(!path_has_tag(&text_path, "binary").unwrap());
+
+        let png_path = dir.path().join("image");
+        fs::write(&png_path, b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR").unwrap();
+        assert!(path_has_tag(&png_path, "png").unwrap());
+        assert!(!path_has_tag(&png_path, "text").unwrap());
+
+        for path in [&script_path, &tool_path, &text_path, &png_path] {
+            let tags = tags_from_path(path).unwrap();
+            for tag in ["python", "text", "binary", "png", "executable", "file"] {
+                assert_eq!(path_has_tag(path, tag).unwrap(), tags.contains(tag));
+            }
+        }
+
+        assert!(path_has_tag(dir.path(), "directory").unwrap());
+        assert!(!path_has_tag(dir.path(), "file").unwrap());
+        assert!(matches!(
+            path_has_tag(dir.path().join("missing"), "file"),
+            Err(IdentifyError::PathNotFound { .. })
+        ));
+    }
+
     #[test]
     fn test_tags_from_path_directory() {
         let dir = tempdir().unwrap();
@@ -1146,7 +4373,7 @@ mod tests {
     fn test_tags_from_path_binary_file() {
         let dir = tempdir().unwrap();
         let binary_path = dir.path().join("binary");
-        fs::write(&binary_path, &[0x7f, 0x45, 0x4c, 0x46, 0x02, 0x01, 0x01]).unwrap();
+        fs::write(&binary_path, [0x7f, 0x45, 0x4c, 0x46, 0x02, 0x01, 0x01]).unwrap();
 
         let tags = tags_from_path(&binary_path).unwrap();
         assert!(tags.contains("file"));
@@ -1154,6 +4381,29 @@ mod tests {
         assert!(tags.contains("non-executable"));
     }
 
+    #[test]
+    fn test_extension_in_pathext() {
+        let pathext = ".COM;.EXE;.PS1;.msi";
+        assert!(extension_in_pathext(Path::new("setup.exe"), pathext));
+        assert!(extension_in_pathext(Path::new("SETUP.EXE"), pathext));
+        assert!(extension_in_pathext(Path::new("run.ps1"), pathext));
+        assert!(extension_in_pathext(Path::new("install.MSI"), pathext));
+        assert!(!extension_in_pathext(Path::new("script.bat"), pathext));
+        assert!(!extension_in_pathext(Path::new("noextension"), pathext));
+
+        // Entries without leading dots and stray separators are tolerated
+        assert!(extension_in_pathext(Path::new("tool.com"), "com;;exe;"));
+
+        assert!(extension_in_pathext(
+            Path::new("build.cmd"),
+            DEFAULT_PATHEXT
+        ));
+        assert!(!extension_in_pathext(
+            Path::new("notes.txt"),
+            DEFAULT_PATHEXT
+        ));
+    }
+
     #[test]
     fn test_file_is_text_simple() {
         let dir = tempdir().unwrap();
@@ -1168,6 +4418,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_io_error_includes_path() {
+        let dir = tempdir().unwrap();
+
+        // Opening a directory as a file succeeds on Unix but reading fails
+        let err = file_is_text(dir.path()).unwrap_err();
+        assert!(matches!(err, IdentifyError::IoErrorAt { .. }));
+        assert!(err.to_string().contains(&*dir.path().to_string_lossy()));
+
+        // Reader-based functions have no path to report
+        struct FailingReader;
+        impl Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("boom"))
+            }
+        }
+        let err = is_text(FailingReader).unwrap_err();
+        assert!(matches!(err, IdentifyError::IoError { .. }));
+    }
+
     // Test extensions that need binary check
     #[test]
     fn test_plist_binary_detection() {
@@ -1179,7 +4449,7 @@ mod tests {
             0x62, 0x70, 0x6c, 0x69, 0x73, 0x74, 0x30, 0x30, // "bplist00"
             0xd1, 0x01, 0x02, 0x5f, 0x10, 0x0f,
         ];
-        fs::write(&plist_path, &binary_plist).unwrap();
+        fs::write(&plist_path, binary_plist).unwrap();
 
         let tags = tags_from_path(&plist_path).unwrap();
         assert!(tags.contains("plist"));
@@ -1232,6 +4502,20 @@ mod tests {
         assert!(tags.contains("gzip"));
     }
 
+    #[test]
+    fn test_tags_from_content_ignores_name() {
+        let tags = tags_from_content(Cursor::new(b"\x7fELF\x02\x01\x01\x00")).unwrap();
+        assert_eq!(tags, HashSet::from(["binary", "elf"]));
+
+        let tags = tags_from_content(Cursor::new(b"#!/bin/bash\necho hi")).unwrap();
+        assert!(tags.contains("shell"));
+        assert!(tags.contains("bash"));
+        assert!(tags.contains("text"));
+
+        let tags = tags_from_content(Cursor::new(b"plain words")).unwrap();
+        assert_eq!(tags, HashSet::from(["text"]));
+    }
+
     // Test FileIdentifier builder pattern
     #[test]
     fn test_file_identifier_default() {
@@ -1284,6 +4568,797 @@ mod tests {
         assert!(!tags.contains("python"));
     }
 
+    #[test]
+    fn test_file_identifier_max_content_bytes() {
+        let dir = tempdir().unwrap();
+        let large_file = dir.path().join("large");
+        let file = fs::File::create(&large_file).unwrap();
+        file.set_len(1024 * 1024 * 1024).unwrap(); // Sparse, reads as all zeros
+        drop(file);
+
+        let identifier = FileIdentifier::new().max_content_bytes(4096);
+        let tags = identifier.identify(&large_file).unwrap();
+        assert!(tags.contains("binary"));
+
+        // Files under the limit are still analyzed
+        let small_file = dir.path().join("small");
+        fs::write(&small_file, "hello").unwrap();
+        let tags = identifier.identify(&small_file).unwrap();
+        assert!(tags.contains("text"));
+
+        // Existing encoding tags are preserved for large files
+        let large_text = dir.path().join("large.txt");
+        fs::File::create(&large_text)
+            .unwrap()
+            .set_len(8192)
+            .unwrap();
+        let tags = identifier.identify(&large_text).unwrap();
+        assert!(tags.contains("text"));
+        assert!(!tags.contains("binary"));
+
+        // The length hint can be ignored entirely
+        let big_text = dir.path().join("big");
+        fs::write(&big_text, "hello\n".repeat(1024)).unwrap();
+        assert!(identifier.identify(&big_text).unwrap().contains("binary"));
+        let tags = identifier
+            .always_read_content()
+            .identify(&big_text)
+            .unwrap();
+        assert!(tags.contains("text"));
+    }
+
+    #[test]
+    fn test_file_identifier_max_content_bytes_skips_shebang() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("script");
+        let mut content = b"#!/usr/bin/env python3\n".to_vec();
+        content.resize(16 * 1024, b'#');
+        fs::write(&script, content).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let identifier = FileIdentifier::new().max_content_bytes(10);
+        let mut opened = false;
+        let mut sample = ContentSample::new(
+            || {
+                opened = true;
+                fs::File::open(&script)
+            },
+            CONTENT_SAMPLE_SIZE,
+        );
+        let tags = identifier
+            .identify_path_with_sample(&script, &mut sample)
+            .unwrap()
+            .into_tags();
+        drop(sample);
+        assert!(!opened);
+        assert_eq!(tags, HashSet::from(["file", "executable", "binary"]));
+    }
+
+    #[test]
+    fn test_file_identifier_reads_content_without_length_hint() {
+        // Pseudo-files return their content in short reads with no known length
+        struct ShortReads<'a>(&'a [u8]);
+
+        impl Read for ShortReads<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = self.0.len().min(buf.len()).min(7);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let identifier = FileIdentifier::new().max_content_bytes(0);
+        let tags = identifier
+            .identify_parts("status", false, ShortReads(b"Name:\tbash\nState:\tS\n"))
+            .unwrap();
+        assert!(tags.contains("text"));
+
+        let tags = identifier
+            .identify_parts("blob", false, ShortReads(b"Name:\x00\x01\x02"))
+            .unwrap();
+        assert!(tags.contains("binary"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_file_identifier_proc_zero_length_file() {
+        let status = Path::new("/proc/self/status");
+        if fs::metadata(status).map_or(true, |m| m.len() != 0) {
+            return; // No procfs available
+        }
+
+        let identifier = FileIdentifier::new().max_content_bytes(0);
+        let tags = identifier.identify(status).unwrap();
+        assert!(tags.contains("file"));
+        assert!(tags.contains("text"));
+        assert!(tags_from_path(status).unwrap().contains("text"));
+    }
+
+    #[test]
+    fn test_file_identifier_flag_conflicts() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().flag_conflicts();
+
+        let bogus_json = dir.path().join("report.json");
+        fs::write(&bogus_json, [0u8; 64]).unwrap();
+        let tags = identifier.identify(&bogus_json).unwrap();
+        assert!(tags.contains("json"));
+        assert!(tags.contains("content-mismatch"));
+
+        let real_json = dir.path().join("data.json");
+        fs::write(&real_json, r#"{"key": "value"}"#).unwrap();
+        let tags = identifier.identify(&real_json).unwrap();
+        assert!(!tags.contains("content-mismatch"));
+
+        let text_png = dir.path().join("image.png");
+        fs::write(&text_png, "not really an image").unwrap();
+        let tags = identifier.identify(&text_png).unwrap();
+        assert!(tags.contains("content-mismatch"));
+
+        // Not flagged unless opted in
+        let tags = FileIdentifier::new().identify(&bogus_json).unwrap();
+        assert!(!tags.contains("content-mismatch"));
+    }
+
+    #[test]
+    fn test_file_identifier_detect_script() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().detect_script();
+
+        let japanese = dir.path().join("japanese.txt");
+        fs::write(&japanese, "これは日本語のテキストファイルです。").unwrap();
+        let tags = identifier.identify(&japanese).unwrap();
+        assert!(tags.contains("cjk"));
+
+        let english = dir.path().join("english.txt");
+        fs::write(&english, "This is an English text file.").unwrap();
+        let tags = identifier.identify(&english).unwrap();
+        assert!(tags.contains("latin"));
+
+        let mixed = dir.path().join("mixed.txt");
+        fs::write(&mixed, "abc абв").unwrap();
+        let tags = identifier.identify(&mixed).unwrap();
+        assert!(tags.contains("mixed-script"));
+
+        let binary = dir.path().join("binary");
+        fs::write(&binary, b"abc\x00def").unwrap();
+        let tags = identifier.identify(&binary).unwrap();
+        assert!(tags.contains("binary"));
+        assert!(!tags.contains("latin"));
+
+        // Invalid UTF-8 text gets no script tag
+        let latin1 = dir.path().join("latin1.txt");
+        fs::write(&latin1, b"caf\xe9 cr\xe8me").unwrap();
+        let tags = identifier.identify(&latin1).unwrap();
+        assert!(!tags.contains("latin"));
+
+        // Not tagged unless opted in
+        let tags = FileIdentifier::new().identify(&english).unwrap();
+        assert!(!tags.contains("latin"));
+    }
+
+    #[test]
+    fn test_file_identifier_validate_utf8() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().validate_utf8();
+
+        let utf8 = dir.path().join("utf8.txt");
+        fs::write(&utf8, "caf\u{e9} cr\u{e8}me br\u{fb}l\u{e9}e").unwrap();
+        let tags = identifier.identify(&utf8).unwrap();
+        assert!(tags.contains("valid-utf8"));
+        assert!(!tags.contains("invalid-utf8"));
+
+        let latin1 = dir.path().join("latin1.txt");
+        fs::write(&latin1, b"caf\xe9 cr\xe8me").unwrap();
+        let tags = identifier.identify(&latin1).unwrap();
+        assert!(tags.contains("text"));
+        assert!(tags.contains("invalid-utf8"));
+
+        // A multi-byte character straddling the 1KB sample window
+        let mut straddling = vec![b'a'; 1023];
+        straddling.extend("\u{e9}".as_bytes());
+        let straddle = dir.path().join("straddle.txt");
+        fs::write(&straddle, &straddling).unwrap();
+        let tags = identifier.identify(&straddle).unwrap();
+        assert!(tags.contains("valid-utf8"));
+
+        // A truncated sequence at the real end of a short file is invalid
+        let truncated = dir.path().join("truncated.txt");
+        fs::write(&truncated, b"abc\xc3").unwrap();
+        let tags = identifier.identify(&truncated).unwrap();
+        assert!(tags.contains("invalid-utf8"));
+
+        let binary = dir.path().join("binary");
+        fs::write(&binary, b"abc\x00def").unwrap();
+        let tags = identifier.identify(&binary).unwrap();
+        assert!(!tags.contains("valid-utf8"));
+        assert!(!tags.contains("invalid-utf8"));
+
+        // Not tagged unless opted in
+        let tags = FileIdentifier::new().identify(&utf8).unwrap();
+        assert!(!tags.contains("valid-utf8"));
+    }
+
+    #[test]
+    fn test_file_identifier_treat_blank_as_empty() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().treat_blank_as_empty();
+
+        let bom_only = dir.path().join("bom.txt");
+        fs::write(&bom_only, b"\xef\xbb\xbf").unwrap();
+        let tags = identifier.identify(&bom_only).unwrap();
+        assert!(tags.contains("text"));
+        assert!(tags.contains("blank"));
+
+        let whitespace = dir.path().join("spaces.py");
+        fs::write(&whitespace, "  \n\n\t \r\n").unwrap();
+        let tags = identifier.identify(&whitespace).unwrap();
+        assert!(tags.contains("python"));
+        assert!(tags.contains("blank"));
+
+        let content = dir.path().join("content.txt");
+        fs::write(&content, "\u{feff}  x\n").unwrap();
+        let tags = identifier.identify(&content).unwrap();
+        assert!(!tags.contains("blank"));
+
+        // Content past the sample window is unknown, so it is not blank
+        let large = dir.path().join("large.txt");
+        fs::write(&large, " ".repeat(CONTENT_SAMPLE_SIZE)).unwrap();
+        let tags = identifier.identify(&large).unwrap();
+        assert!(!tags.contains("blank"));
+
+        // Not tagged unless opted in
+        let tags = FileIdentifier::new().identify(&bom_only).unwrap();
+        assert!(!tags.contains("blank"));
+    }
+
+    #[test]
+    fn test_file_identifier_fast_language_only() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().fast_language_only();
+
+        let script = dir.path().join("script.py");
+        fs::write(&script, "print('hello')\n").unwrap();
+        let tags = identifier.identify(&script).unwrap();
+        assert_eq!(tags, HashSet::from(["file", "non-executable", "python"]));
+        assert!(tags.is_subset(&FileIdentifier::new().identify(&script).unwrap()));
+
+        let workflow_dir = dir.path().join(".github").join("workflows");
+        fs::create_dir_all(&workflow_dir).unwrap();
+        let workflow = workflow_dir.join("ci.yml");
+        fs::write(&workflow, "on: push\n").unwrap();
+        let tags = identifier.identify(&workflow).unwrap();
+        assert!(tags.contains("github-workflow"));
+        assert!(!tags.contains("text"));
+
+        // Unrecognized names still get full identification
+        let notes = dir.path().join("notes");
+        fs::write(&notes, "hello\n").unwrap();
+        let tags = identifier.identify(&notes).unwrap();
+        assert!(tags.contains("text"));
+
+        let tags = identifier
+            .identify_parts("lib.rs", false, Cursor::new(b"fn main() {}"))
+            .unwrap();
+        assert_eq!(tags, HashSet::from(["file", "non-executable", "rust"]));
+    }
+
+    #[test]
+    fn test_file_identifier_content_sniff_bytes() {
+        let dir = tempdir().unwrap();
+        let mut content = vec![b'a'; 2000];
+        content.extend(b"\x00\x01\x02");
+        let path = dir.path().join("data");
+        fs::write(&path, &content).unwrap();
+
+        // The null bytes sit past the default 1KB window
+        let tags = FileIdentifier::new().identify(&path).unwrap();
+        assert!(tags.contains("text"));
+
+        let identifier = FileIdentifier::new().content_sniff_bytes(4096);
+        let tags = identifier.identify(&path).unwrap();
+        assert!(tags.contains("binary"));
+        assert!(!tags.contains("text"));
+        let tags = identifier
+            .identify_parts("data", false, content.as_slice())
+            .unwrap();
+        assert!(tags.contains("binary"));
+
+        // The size limit still decides whether content is read at all
+        let tags = identifier.max_content_bytes(100).identify(&path).unwrap();
+        assert!(tags.contains("binary"));
+        let small = dir.path().join("small");
+        fs::write(&small, "hello\n").unwrap();
+        let tags = FileIdentifier::new()
+            .content_sniff_bytes(4096)
+            .max_content_bytes(100)
+            .identify(&small)
+            .unwrap();
+        assert!(tags.contains("text"));
+    }
+
+    #[test]
+    fn test_file_identifier_detect_modelines() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().detect_modelines();
+
+        let vim_ruby = dir.path().join("Guardfile-local");
+        fs::write(&vim_ruby, "guard :rspec do\nend\n# vim: set ft=ruby :\n").unwrap();
+        let tags = identifier.identify(&vim_ruby).unwrap();
+        assert!(tags.contains("text"));
+        assert!(tags.contains("ruby"));
+
+        // Not tagged unless opted in
+        let tags = FileIdentifier::new().identify(&vim_ruby).unwrap();
+        assert!(!tags.contains("ruby"));
+
+        let cases: [(&[u8], &str); 4] = [
+            (b"# -*- mode: python; coding: utf-8 -*-\nx = 1\n", "python"),
+            (b"// -*- C++ -*-\nint x;\n", "c++"),
+            (b"/* vim:ft=cpp:ts=4 */\nint x;\n", "c++"),
+            (b"\n\nkey: value\n# vi: filetype=yaml\n", "yaml"),
+        ];
+        for (content, language) in cases {
+            let tags = identifier.identify_parts("config", false, content).unwrap();
+            assert!(tags.contains(language), "{language}: {tags:?}");
+        }
+
+        // Unknown languages, non-modeline text and binary files add nothing
+        for content in [
+            &b"# vim: set ft=nosuchlang :\n"[..],
+            b"the evim:ft=ruby editor\n",
+            b"\x00\x01 vim: ft=ruby\n",
+        ] {
+            let tags = identifier.identify_parts("data", false, content).unwrap();
+            assert!(!tags.contains("ruby"), "{tags:?}");
+            assert_eq!(tags.len(), 3, "{tags:?}");
+        }
+    }
+
+    #[test]
+    fn test_file_identifier_tag_unknown() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().tag_unknown();
+
+        let unknown = dir.path().join("unknown.xyz");
+        fs::write(&unknown, "hello\n").unwrap();
+        let tags = identifier.identify(&unknown).unwrap();
+        assert_eq!(
+            tags,
+            HashSet::from(["file", "non-executable", "text", "unknown"])
+        );
+        let tags = FileIdentifier::new().identify(&unknown).unwrap();
+        assert!(!tags.contains("unknown"));
+
+        // Any format tag, from any step, means the file is known
+        let tags = identifier
+            .identify_parts("script.py", false, Cursor::new(b"x = 1"))
+            .unwrap();
+        assert!(!tags.contains("unknown"));
+        let tags = identifier
+            .identify_parts("blob", false, Cursor::new(b"\x7fELF\x02"))
+            .unwrap();
+        assert!(tags.contains("elf"));
+        assert!(!tags.contains("unknown"));
+
+        assert_eq!(
+            tags_from_filename_or_unknown("unknown.xyz"),
+            HashSet::from(["unknown"])
+        );
+        assert!(tags_from_filename("unknown.xyz").is_empty());
+        assert_eq!(
+            tags_from_filename_or_unknown("main.rs"),
+            tags_from_filename("main.rs")
+        );
+    }
+
+    #[test]
+    fn test_file_identifier_tag_hidden() {
+        let dir = tempdir().unwrap();
+        let identifier = FileIdentifier::new().tag_hidden();
+
+        let gitignore = dir.path().join(".gitignore");
+        fs::write(&gitignore, "target/\n").unwrap();
+        let tags = identifier.identify(&gitignore).unwrap();
+        assert!(tags.contains("hidden"));
+        assert!(tags.contains("gitignore"));
+
+        let readme = dir.path().join("README.md");
+        fs::write(&readme, "# Title\n").unwrap();
+        let tags = identifier.identify(&readme).unwrap();
+        assert!(!tags.contains("hidden"));
+        assert!(tags.contains("markdown"));
+
+        let hidden_dir = dir.path().join(".cache");
+        fs::create_dir(&hidden_dir).unwrap();
+        let tags = identifier.identify(&hidden_dir).unwrap();
+        assert_eq!(tags, HashSet::from(["directory", "hidden"]));
+
+        let tags = identifier
+            .identify_parts(".env", false, Cursor::new(b"KEY=value\n"))
+            .unwrap();
+        assert!(tags.contains("hidden"));
+
+        // Not tagged unless opted in
+        let tags = FileIdentifier::new().identify(&gitignore).unwrap();
+        assert!(!tags.contains("hidden"));
+    }
+
+    #[test]
+    fn test_marker_tags_do_not_count_as_formats() {
+        let dir = tempdir().unwrap();
+        let mystery = dir.path().join(".mystery");
+        fs::write(&mystery, "some notes\n").unwrap();
+
+        // The hidden tag neither cuts content analysis short...
+        let tags = FileIdentifier::new()
+            .tag_hidden()
+            .fast_language_only()
+            .identify(&mystery)
+            .unwrap();
+        assert_eq!(
+            tags,
+            HashSet::from(["file", "non-executable", "hidden", "text"])
+        );
+
+        // ...nor stands in for a format
+        let tags = FileIdentifier::new()
+            .tag_hidden()
+            .tag_unknown()
+            .identify(&mystery)
+            .unwrap();
+        assert_eq!(
+            tags,
+            HashSet::from(["file", "non-executable", "hidden", "text", "unknown"])
+        );
+
+        // Other markers from content checks and shebangs behave the same
+        let tags = FileIdentifier::new()
+            .validate_utf8()
+            .treat_blank_as_empty()
+            .detect_script()
+            .tag_unknown()
+            .identify_parts("notes", false, Cursor::new(b"   \n"))
+            .unwrap();
+        assert!(tags.is_superset(&HashSet::from(["valid-utf8", "blank", "unknown"])));
+        let tags = FileIdentifier::new()
+            .tag_unknown_shebang_as_script()
+            .tag_unknown()
+            .identify_parts("run", true, Cursor::new(b"#!/opt/weird/tool\n"))
+            .unwrap();
+        assert!(tags.is_superset(&HashSet::from(["script", "unknown"])));
+
+        // A real format is still found
+        let tags = FileIdentifier::new()
+            .tag_hidden()
+            .tag_unknown()
+            .identify_parts(".gitignore", false, Cursor::new(b"target/\n"))
+            .unwrap();
+        assert!(tags.contains("gitignore"));
+        assert!(!tags.contains("unknown"));
+    }
+
+    #[test]
+    fn test_file_identifier_on_content_error() {
+        struct FailingReader;
+        impl Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("boom"))
+            }
+        }
+
+        let identify = |policy| {
+            FileIdentifier::new()
+                .on_content_error(policy)
+                .identify_parts("blob", false, FailingReader)
+        };
+        assert!(matches!(
+            identify(ContentErrorPolicy::Propagate),
+            Err(IdentifyError::IoError { .. })
+        ));
+        assert_eq!(
+            identify(ContentErrorPolicy::AssumeBinary).unwrap(),
+            HashSet::from(["file", "non-executable", "binary"])
+        );
+        assert_eq!(
+            identify(ContentErrorPolicy::AssumeText).unwrap(),
+            HashSet::from(["file", "non-executable", "text"])
+        );
+        assert_eq!(
+            identify(ContentErrorPolicy::Omit).unwrap(),
+            HashSet::from(["file", "non-executable"])
+        );
+
+        // The fallback never overrides an encoding from the filename
+        let tags = FileIdentifier::new()
+            .flag_conflicts()
+            .on_content_error(ContentErrorPolicy::AssumeBinary)
+            .identify_parts("script.py", false, FailingReader)
+            .unwrap();
+        assert!(tags.contains("text"));
+        assert!(!tags.contains("binary"));
+
+        // A real file that fails to read: /proc/self/mem has no mapping at 0
+        let path = Path::new("/proc/self/mem");
+        if path.exists() {
+            let err = FileIdentifier::new().identify(path).unwrap_err();
+            assert!(matches!(err, IdentifyError::IoErrorAt { .. }));
+            let tags = FileIdentifier::new()
+                .on_content_error(ContentErrorPolicy::AssumeBinary)
+                .identify(path)
+                .unwrap();
+            assert!(tags.contains("binary"));
+        }
+    }
+
+    #[test]
+    fn test_file_identifier_tag_unknown_shebang_as_script() {
+        let dir = tempdir().unwrap();
+        let script_file = dir.path().join("tool-runner");
+        fs::write(&script_file, "#!/opt/weird/tool --flag\nrun things").unwrap();
+        let mut perms = fs::metadata(&script_file).unwrap().permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&script_file, perms).unwrap();
+
+        let tags = FileIdentifier::new().identify(&script_file).unwrap();
+        assert!(!tags.contains("script"));
+
+        let identifier = FileIdentifier::new().tag_unknown_shebang_as_script();
+        let tags = identifier.identify(&script_file).unwrap();
+        assert!(tags.contains("script"));
+        assert!(tags.contains("executable"));
+
+        // Recognized interpreters are unaffected
+        let python_file = dir.path().join("python-runner");
+        fs::write(&python_file, "#!/usr/bin/env python3\nprint('hi')").unwrap();
+        fs::set_permissions(&python_file, fs::Permissions::from_mode(0o755)).unwrap();
+        let tags = identifier.identify(&python_file).unwrap();
+        assert!(tags.contains("python"));
+        assert!(!tags.contains("script"));
+    }
+
+    #[test]
+    fn test_file_identifier_resolve_polyglot_shebangs() {
+        let dir = tempdir().unwrap();
+        let launcher = dir.path().join("launcher");
+        fs::write(
+            &launcher,
+            "#!/usr/bin/env bash -c 'exec python3 \"$0\" \"$@\"'\nprint('hi')\n",
+        )
+        .unwrap();
+        fs::set_permissions(&launcher, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let tags = FileIdentifier::new().identify(&launcher).unwrap();
+        assert!(tags.contains("bash"));
+        assert!(!tags.contains("python"));
+
+        let identifier = FileIdentifier::new().resolve_polyglot_shebangs();
+        let tags = identifier.identify(&launcher).unwrap();
+        assert!(tags.is_superset(&HashSet::from(["shell", "bash", "python", "python3"])));
+
+        // A -c command without exec names no interpreter
+        let plain = dir.path().join("plain");
+        fs::write(&plain, "#!/bin/sh -c 'echo python3'\n").unwrap();
+        fs::set_permissions(&plain, fs::Permissions::from_mode(0o755)).unwrap();
+        let tags = identifier.identify(&plain).unwrap();
+        assert!(tags.contains("sh"));
+        assert!(!tags.contains("python"));
+    }
+
+    #[test]
+    fn test_file_identifier_identify_timed() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run");
+        fs::write(&script, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let identifier = FileIdentifier::new();
+        let (tags, timings) = identifier.identify_timed(&script).unwrap();
+        assert_eq!(tags, identifier.identify(&script).unwrap());
+        assert!(timings.total() > Duration::ZERO);
+        assert!(timings.metadata > Duration::ZERO);
+        assert!(timings.shebang > Duration::ZERO);
+
+        // Skipped stages report no time
+        let (_, timings) = FileIdentifier::new()
+            .skip_content_analysis()
+            .identify_timed(dir.path().join("run"))
+            .unwrap();
+        assert_eq!(timings.content, Duration::ZERO);
+        let (_, timings) = identifier.identify_timed(dir.path()).unwrap();
+        assert_eq!(timings.total(), timings.metadata);
+    }
+
+    #[test]
+    fn test_file_identifier_executable_policy() {
+        let dir = tempdir().unwrap();
+        let plain = dir.path().join("notes");
+        let script = dir.path().join("run");
+        fs::write(&plain, "just some notes\n").unwrap();
+        fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+        for path in [&plain, &script] {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o777)).unwrap();
+        }
+
+        let identifier = FileIdentifier::new();
+        assert!(identifier.identify(&plain).unwrap().contains("executable"));
+        assert!(identifier.identify(&script).unwrap().contains("executable"));
+
+        let identifier = FileIdentifier::new().executable_policy(ExecutablePolicy::ShebangPresence);
+        let tags = identifier.identify(&plain).unwrap();
+        assert!(tags.contains("non-executable"));
+        let tags = identifier.identify(&script).unwrap();
+        assert!(tags.contains("executable"));
+        assert!(tags.contains("sh"));
+
+        let identifier = FileIdentifier::new().executable_policy(ExecutablePolicy::Never);
+        assert!(
+            identifier
+                .identify(&plain)
+                .unwrap()
+                .contains("non-executable")
+        );
+        let tags = identifier.identify(&script).unwrap();
+        assert!(tags.contains("non-executable"));
+        assert!(!tags.contains("sh"));
+
+        // Files that are not read cannot show a shebang; skipping only the
+        // shebang still reads the content for encoding detection
+        for (identifier, reads_content) in [
+            (FileIdentifier::new().max_content_bytes(4), false),
+            (FileIdentifier::new().skip_content_analysis(), false),
+            (FileIdentifier::new().skip_shebang_analysis(), true),
+        ] {
+            let identifier = identifier.executable_policy(ExecutablePolicy::ShebangPresence);
+            let mut opened = false;
+            let mut sample = ContentSample::new(
+                || {
+                    opened = true;
+                    fs::File::open(&script)
+                },
+                CONTENT_SAMPLE_SIZE,
+            );
+            let tags = identifier
+                .identify_path_with_sample(&script, &mut sample)
+                .unwrap()
+                .into_tags();
+            drop(sample);
+            assert_eq!(opened, reads_content);
+            assert!(tags.contains("non-executable"));
+            assert!(!tags.contains("sh"));
+        }
+    }
+
+    #[test]
+    fn test_file_identifier_identify_explained() {
+        let dir = tempdir().unwrap();
+        let py_file = dir.path().join("x.py");
+        fs::write(&py_file, "print('hello')").unwrap();
+
+        let identifier = FileIdentifier::new();
+        let explained = identifier.identify_explained(&py_file).unwrap();
+        let origin = |tag: &str| {
+            explained
+                .iter()
+                .find(|(t, _)| t == tag)
+                .map(|(_, origin)| *origin)
+        };
+        assert_eq!(origin("file"), Some(TagOrigin::FileType));
+        assert_eq!(origin("non-executable"), Some(TagOrigin::Permission));
+        assert_eq!(origin("python"), Some(TagOrigin::Extension));
+        assert_eq!(origin("text"), Some(TagOrigin::Extension));
+
+        // Explained tags are exactly the identified tags
+        let tags: TagSet = identifier.identify(&py_file).unwrap();
+        assert_eq!(explained.len(), tags.len());
+        assert!(explained.iter().all(|(tag, _)| tags.contains(tag.as_str())));
+
+        let script = dir.path().join("run");
+        fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let explained = identifier.identify_explained(&script).unwrap();
+        assert!(explained.contains(&("sh".to_string(), TagOrigin::Shebang)));
+        assert!(explained.contains(&("text".to_string(), TagOrigin::Content)));
+
+        let setup = dir.path().join("setup.cfg");
+        fs::write(&setup, "[metadata]\n").unwrap();
+        let explained = identifier.identify_explained(&setup).unwrap();
+        assert!(explained.contains(&("ini".to_string(), TagOrigin::Name)));
+
+        let mut custom_extensions = std::collections::HashMap::new();
+        custom_extensions.insert("py".to_string(), HashSet::from(["text", "custom"]));
+        let explained = FileIdentifier::new()
+            .with_custom_extensions(custom_extensions)
+            .identify_explained(&py_file)
+            .unwrap();
+        assert!(explained.contains(&("custom".to_string(), TagOrigin::Custom)));
+
+        let explained = identifier.identify_explained(dir.path()).unwrap();
+        assert_eq!(
+            explained,
+            vec![("directory".to_string(), TagOrigin::FileType)]
+        );
+    }
+
+    #[test]
+    fn test_file_identifier_identify_parts() {
+        let identifier = FileIdentifier::new();
+        let content = b"#!/usr/bin/env python3\nprint('hi')\n";
+
+        // Executability comes from the explicit flag, not the filesystem
+        let tags = identifier
+            .identify_parts("run-me", true, std::io::Cursor::new(content))
+            .unwrap();
+        assert_eq!(
+            tags,
+            HashSet::from(["file", "executable", "python", "python3", "text"])
+        );
+
+        // Shebangs are only consulted for executables
+        let tags = identifier
+            .identify_parts("run-me", false, std::io::Cursor::new(content))
+            .unwrap();
+        assert_eq!(tags, HashSet::from(["file", "non-executable", "text"]));
+
+        // Filename tags win over the shebang, and only the basename is used
+        let tags = identifier
+            .identify_parts("src/app.js", true, std::io::Cursor::new(content))
+            .unwrap();
+        assert!(tags.contains("javascript"));
+        assert!(!tags.contains("python"));
+
+        let tags = identifier
+            .identify_parts("blob", false, std::io::Cursor::new(b"\x7fELF\x00\x01"))
+            .unwrap();
+        assert!(tags.contains("binary"));
+    }
+
+    #[test]
+    fn test_file_identifier_identify_many() {
+        let blobs = vec![
+            ("main.rs".to_string(), b"fn main() {}\n".to_vec()),
+            ("run-me".to_string(), b"#!/bin/sh\necho hi\n".to_vec()),
+            (
+                "image.bin".to_string(),
+                b"\x89PNG\r\n\x1a\n\x00\x00".to_vec(),
+            ),
+            ("empty".to_string(), Vec::new()),
+            (
+                "dir/notes.txt".to_string(),
+                "caf\u{e9}".repeat(400).into_bytes(),
+            ),
+        ];
+
+        let identifier = FileIdentifier::new().detect_script();
+        let results = identifier.identify_many(blobs.clone());
+        assert_eq!(results.len(), blobs.len());
+
+        for ((filename, content), result) in blobs.iter().zip(results) {
+            let expected = identifier
+                .identify_parts(filename, false, content.as_slice())
+                .unwrap();
+            assert_eq!(result.unwrap(), expected, "{filename}");
+        }
+    }
+
+    #[test]
+    fn test_file_identifier_identify_parts_matches_identify() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("test.myext");
+        fs::write(&file, "custom content").unwrap();
+
+        let mut custom_extensions = std::collections::HashMap::new();
+        custom_extensions.insert("myext".to_string(), HashSet::from(["custom", "text"]));
+        let identifier = FileIdentifier::new().with_custom_extensions(custom_extensions);
+
+        let from_path = identifier.identify(&file).unwrap();
+        let from_parts = identifier
+            .identify_parts("test.myext", false, fs::File::open(&file).unwrap())
+            .unwrap();
+        assert_eq!(from_path, from_parts);
+    }
+
     #[test]
     fn test_file_identifier_custom_extensions() {
         let dir = tempdir().unwrap();
@@ -1302,6 +5377,218 @@ mod tests {
         assert!(tags.contains("non-executable"));
     }
 
+    #[test]
+    fn test_file_identifier_custom_extensions_ignore_key_case() {
+        let dir = tempdir().unwrap();
+        let custom_extensions =
+            std::collections::HashMap::from([("MyExt".to_string(), HashSet::from(["custom"]))]);
+        let identifier = FileIdentifier::new().with_custom_extensions(custom_extensions);
+
+        for name in ["file.myext", "file.MYEXT", "file.MyExt"] {
+            let path = dir.path().join(name);
+            fs::write(&path, "custom content").unwrap();
+            let tags = identifier.identify(&path).unwrap();
+            assert!(tags.contains("custom"), "{name}: {tags:?}");
+        }
+    }
+
+    #[test]
+    fn test_file_identifier_custom_extension_suppresses_builtin() {
+        let dir = tempdir().unwrap();
+        let header_file = dir.path().join("test.h");
+        fs::write(&header_file, "int main(void);").unwrap();
+
+        let mut custom_extensions = std::collections::HashMap::new();
+        custom_extensions.insert("h".to_string(), TagSet::new());
+
+        let identifier = FileIdentifier::new().with_custom_extensions(custom_extensions);
+        let tags = identifier.identify(&header_file).unwrap();
+
+        assert_eq!(tags, HashSet::from(["file", "non-executable", "text"]));
+    }
+
+    #[test]
+    fn test_file_identifier_filename_patterns() {
+        let dir = tempdir().unwrap();
+        let test_file = dir.path().join("parser.test.ts");
+        let plain_file = dir.path().join("parser.ts");
+        let docker_file = dir.path().join("Dockerfile.dev");
+        fs::write(&test_file, "it('parses', () => {});").unwrap();
+        fs::write(&plain_file, "export {};").unwrap();
+        fs::write(&docker_file, "FROM alpine").unwrap();
+
+        let identifier = FileIdentifier::new().with_filename_patterns(vec![
+            (
+                glob::Pattern::new("*.test.ts").unwrap(),
+                HashSet::from(["text", "ts", "test"]),
+            ),
+            (
+                glob::Pattern::new("Dockerfile.*").unwrap(),
+                HashSet::from(["text", "dockerfile"]),
+            ),
+            (
+                glob::Pattern::new("*.ts").unwrap(),
+                HashSet::from(["text", "never-reached"]),
+            ),
+        ]);
+
+        let tags = identifier.identify(&test_file).unwrap();
+        assert!(tags.contains("test"));
+        assert!(!tags.contains("never-reached"));
+
+        let tags = identifier.identify(&docker_file).unwrap();
+        assert!(tags.contains("dockerfile"));
+
+        let tags = identifier.identify(&plain_file).unwrap();
+        assert!(tags.contains("never-reached"));
+
+        // Patterns match the filename component, not the full path
+        let identifier = FileIdentifier::new().with_filename_patterns(vec![(
+            glob::Pattern::new("*/parser.ts").unwrap(),
+            HashSet::from(["text", "never-reached"]),
+        )]);
+        let tags = identifier.identify(&plain_file).unwrap();
+        assert!(!tags.contains("never-reached"));
+        assert!(tags.contains("ts"));
+    }
+
+    #[test]
+    fn test_file_identifier_magic_signatures() {
+        let dir = tempdir().unwrap();
+        let acme = dir.path().join("archive");
+        let acme_v2 = dir.path().join("archive-v2");
+        let named = dir.path().join("archive.txt");
+        let plain = dir.path().join("plain");
+        fs::write(&acme, b"ACME\x00\x01\x02").unwrap();
+        fs::write(&acme_v2, b"ACME2\x00\x01").unwrap();
+        fs::write(&named, b"ACME\x00\x01\x02").unwrap();
+        fs::write(&plain, "hello").unwrap();
+
+        let identifier = FileIdentifier::new().with_magic_signatures(vec![
+            (b"ACME".to_vec(), HashSet::from(["binary", "acme"])),
+            (
+                b"ACME2".to_vec(),
+                HashSet::from(["binary", "acme", "acme-v2"]),
+            ),
+        ]);
+
+        let tags = identifier.identify(&acme).unwrap();
+        assert!(tags.contains("acme"));
+        assert!(!tags.contains("acme-v2"));
+
+        // Longer signatures take precedence regardless of order
+        let tags = identifier.identify(&acme_v2).unwrap();
+        assert!(tags.contains("acme-v2"));
+
+        // Content evidence overrides the filename-derived encoding
+        let tags = identifier.identify(&named).unwrap();
+        assert!(tags.contains("acme"));
+        assert!(tags.contains("binary"));
+        assert!(!tags.contains("text"));
+
+        let tags = identifier.identify(&plain).unwrap();
+        assert!(!tags.contains("acme"));
+        assert!(tags.contains("text"));
+    }
+
+    #[test]
+    fn test_file_identifier_merge() {
+        let dir = tempdir().unwrap();
+        let foo_file = dir.path().join("test.foo");
+        let bar_file = dir.path().join("test.bar");
+        fs::write(&foo_file, "foo").unwrap();
+        fs::write(&bar_file, "bar").unwrap();
+
+        let base = FileIdentifier::new().with_custom_extensions(std::collections::HashMap::from([
+            ("foo".to_string(), HashSet::from(["text", "foo"])),
+            ("bar".to_string(), HashSet::from(["text", "old-bar"])),
+        ]));
+        let overrides = FileIdentifier::new()
+            .skip_shebang_analysis()
+            .with_custom_extensions(std::collections::HashMap::from([(
+                "bar".to_string(),
+                HashSet::from(["text", "bar"]),
+            )]));
+
+        let merged = base.merge(overrides);
+        assert!(merged.skip_shebang_analysis);
+        assert!(!merged.skip_content_analysis);
+
+        let tags = merged.identify(&foo_file).unwrap();
+        assert!(tags.contains("foo"));
+
+        let tags = merged.identify(&bar_file).unwrap();
+        assert!(tags.contains("bar"));
+        assert!(!tags.contains("old-bar"));
+
+        let merged = FileIdentifier::new().merge(FileIdentifier::new().skip_permission_analysis());
+        let tags = merged.identify(&foo_file).unwrap();
+        assert!(!tags.contains("executable"));
+        assert!(!tags.contains("non-executable"));
+
+        let merged = FileIdentifier::new().merge(
+            FileIdentifier::new()
+                .detect_script()
+                .validate_utf8()
+                .detect_modelines()
+                .treat_blank_as_empty()
+                .fast_language_only()
+                .tag_unknown()
+                .tag_hidden()
+                .treat_special_as_file()
+                .tag_unknown_shebang_as_script()
+                .resolve_polyglot_shebangs()
+                .resolve_node_module_type()
+                .always_read_content()
+                .content_sniff_bytes(64)
+                .executable_policy(ExecutablePolicy::Never)
+                .on_content_error(ContentErrorPolicy::AssumeText)
+                .max_symlink_depth(3),
+        );
+        assert!(merged.detect_script);
+        assert!(merged.validate_utf8);
+        assert!(merged.detect_modelines);
+        assert!(merged.treat_blank_as_empty);
+        assert!(merged.fast_language_only);
+        assert!(merged.tag_unknown);
+        assert!(merged.tag_hidden);
+        assert!(merged.treat_special_as_file);
+        assert!(merged.tag_unknown_shebang_as_script);
+        assert!(merged.resolve_polyglot_shebangs);
+        assert!(merged.resolve_node_module_type);
+        assert!(merged.always_read_content);
+        assert_eq!(merged.content_sniff_bytes, 64);
+        assert_eq!(merged.executable_policy, ExecutablePolicy::Never);
+        assert_eq!(merged.content_error_policy, ContentErrorPolicy::AssumeText);
+        assert_eq!(merged.max_symlink_depth, Some(3));
+
+        // Default-valued options in `other` keep the base's settings.
+        let merged = FileIdentifier::new()
+            .content_sniff_bytes(64)
+            .executable_policy(ExecutablePolicy::ShebangPresence)
+            .on_content_error(ContentErrorPolicy::Omit)
+            .max_symlink_depth(3)
+            .merge(FileIdentifier::new());
+        assert_eq!(merged.content_sniff_bytes, 64);
+        assert_eq!(merged.executable_policy, ExecutablePolicy::ShebangPresence);
+        assert_eq!(merged.content_error_policy, ContentErrorPolicy::Omit);
+        assert_eq!(merged.max_symlink_depth, Some(3));
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn test_file_identifier_merge_peek_inside_archives() {
+        let merged = FileIdentifier::new().merge(FileIdentifier::new().peek_inside_archives());
+        assert!(merged.peek_inside_archives);
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_file_identifier_merge_inspect_zip() {
+        let merged = FileIdentifier::new().merge(FileIdentifier::new().inspect_zip());
+        assert!(merged.inspect_zip);
+    }
+
     #[test]
     fn test_file_identifier_chaining() {
         let dir = tempdir().unwrap();
@@ -1383,9 +5670,9 @@ mod tests {
         for input in invalid_utf8_cases {
             // Should not panic, should return empty components for invalid UTF-8
             let result = parse_shebang(Cursor::new(input));
-            match result {
-                Ok(components) => assert!(components.is_empty()),
-                Err(_) => (), // I/O errors are acceptable for invalid data
+            // I/O errors are acceptable for invalid data
+            if let Ok(components) = result {
+                assert!(components.is_empty());
             }
         }
     }
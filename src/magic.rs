@@ -0,0 +1,260 @@
+use crate::tags::{TagSet, tags_from_array};
+
+/// A magic-byte signature identifying a file format from its leading bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct MagicSignature {
+    /// Byte offset at which the signature must appear.
+    pub offset: usize,
+    /// The exact bytes expected at `offset`.
+    pub bytes: &'static [u8],
+    /// Additional `(offset, bytes)` pairs that must also match, for formats
+    /// whose signature is split around variable fields.
+    pub also: &'static [(usize, &'static [u8])],
+    /// Tags applied when the signature matches.
+    pub tags: &'static [&'static str],
+}
+
+impl MagicSignature {
+    /// Check whether this signature matches the given file header.
+    pub fn matches(&self, header: &[u8]) -> bool {
+        std::iter::once((self.offset, self.bytes))
+            .chain(self.also.iter().copied())
+            .all(|(offset, bytes)| {
+                header
+                    .get(offset..offset + bytes.len())
+                    .is_some_and(|window| window == bytes)
+            })
+    }
+}
+
+// Signatures are checked in order and the first match wins, so more specific
+// signatures must come before more general ones sharing a prefix. Signatures
+// tagged `binary` never match a header made only of text bytes, so text that
+// happens to start like a short signature (`MZ`, `PAR1`) stays text.
+
+pub static MAGIC_SIGNATURES: &[MagicSignature] = &[
+    MagicSignature {
+        offset: 0,
+        bytes: b"\x7fELF",
+        also: &[],
+        tags: &["binary", "elf"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\xfe\xed\xfa\xce",
+        also: &[],
+        tags: &["binary", "mach-o"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\xfe\xed\xfa\xcf",
+        also: &[],
+        tags: &["binary", "mach-o"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\xce\xfa\xed\xfe",
+        also: &[],
+        tags: &["binary", "mach-o"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\xcf\xfa\xed\xfe",
+        also: &[],
+        tags: &["binary", "mach-o"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"MZ",
+        also: &[],
+        tags: &["binary", "pe"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"PK\x03\x04",
+        also: &[],
+        tags: &["binary", "zip"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\x1f\x8b",
+        also: &[],
+        tags: &["binary", "gzip"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"BZh",
+        also: &[(4, b"1AY&SY")],
+        tags: &["binary", "bzip2"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\0asm",
+        also: &[],
+        tags: &["binary", "wasm"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"%PDF-",
+        also: &[],
+        tags: &["binary", "pdf"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\x89PNG\r\n\x1a\n",
+        also: &[],
+        tags: &["binary", "image", "png"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\xff\xd8\xff",
+        also: &[],
+        tags: &["binary", "image", "jpeg"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"GIF87a",
+        also: &[],
+        tags: &["binary", "image", "gif"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"GIF89a",
+        also: &[],
+        tags: &["binary", "image", "gif"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"RIFF",
+        also: &[(8, b"WEBP")],
+        tags: &["binary", "image", "webp"],
+    },
+    // "BM" alone is too common at the start of text, so also require the
+    // zeroed reserved fields of the BMP file header
+    MagicSignature {
+        offset: 0,
+        bytes: b"BM",
+        also: &[(6, b"\0\0\0\0")],
+        tags: &["binary", "image", "bitmap"],
+    },
+    // ISO base media files start with an `ftyp` box whose major brand names
+    // the format; other brands (mp4, mov, ...) are left alone
+    MagicSignature {
+        offset: 4,
+        bytes: b"ftyp",
+        also: &[(8, b"avif")],
+        tags: &["binary", "image", "avif"],
+    },
+    MagicSignature {
+        offset: 4,
+        bytes: b"ftyp",
+        also: &[(8, b"avis")],
+        tags: &["binary", "image", "avif"],
+    },
+    MagicSignature {
+        offset: 4,
+        bytes: b"ftyp",
+        also: &[(8, b"heic")],
+        tags: &["binary", "image", "heic"],
+    },
+    MagicSignature {
+        offset: 4,
+        bytes: b"ftyp",
+        also: &[(8, b"heix")],
+        tags: &["binary", "image", "heic"],
+    },
+    MagicSignature {
+        offset: 4,
+        bytes: b"ftyp",
+        also: &[(8, b"mif1")],
+        tags: &["binary", "image", "heif"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\xff\x0a",
+        also: &[],
+        tags: &["binary", "image", "jxl"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"\0\0\0\x0cJXL \r\n\x87\n",
+        also: &[],
+        tags: &["binary", "image", "jxl"],
+    },
+    // OLE compound documents are mostly legacy Office files (.doc, .xls,
+    // .ppt), though other formats such as .msi share the container
+    MagicSignature {
+        offset: 0,
+        bytes: b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1",
+        also: &[],
+        tags: &["binary", "msoffice"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"wOFF",
+        also: &[],
+        tags: &["binary", "font", "woff"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"wOF2",
+        also: &[],
+        tags: &["binary", "font", "woff2"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"SQLite format 3\0",
+        also: &[],
+        tags: &["binary", "sqlite"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"PAR1",
+        also: &[],
+        tags: &["binary", "parquet"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"Obj\x01",
+        also: &[],
+        tags: &["binary", "avro"],
+    },
+    // CPython bytecode starts with a 2-byte magic number that changes with
+    // every release, followed by `\r\n`. Since Python 3.7 a 4-byte flags
+    // word (0 to 3) comes next, whose zero high bytes keep text lines that
+    // happen to end at byte 2 from matching.
+    MagicSignature {
+        offset: 2,
+        bytes: b"\r\n",
+        also: &[(5, b"\0\0\0")],
+        tags: &["binary", "python-bytecode"],
+    },
+    MagicSignature {
+        offset: 0,
+        bytes: b"-----BEGIN ",
+        also: &[],
+        tags: &["text", "pem"],
+    },
+    MagicSignature {
+        offset: 257,
+        bytes: b"ustar",
+        also: &[],
+        tags: &["binary", "tar"],
+    },
+];
+
+/// Get tags for the first magic signature matching the given file header.
+///
+/// A signature tagged `binary` is skipped when the header is entirely text.
+pub fn get_magic_tags(header: &[u8]) -> TagSet {
+    let is_text = std::cell::OnceCell::new();
+    MAGIC_SIGNATURES
+        .iter()
+        .find(|signature| {
+            signature.matches(header)
+                && !(signature.tags.contains(&"binary")
+                    && *is_text.get_or_init(|| crate::bytes_are_text(header)))
+        })
+        .map(|signature| tags_from_array(signature.tags))
+        .unwrap_or_default()
+}
@@ -0,0 +1,24 @@
+//! Convenient re-exports of the most commonly used items.
+//!
+//! ```rust
+//! use file_identify::prelude::*;
+//!
+//! let tags = tags_from_filename("script.py");
+//! assert!(tags.contains(TEXT));
+//! assert_eq!(primary_language(&tags), Some("python"));
+//!
+//! let identifier = FileIdentifier::new().skip_content_analysis();
+//! let tags: TagSet = identifier.identify_parts("run", true, &b"#!/bin/sh\n"[..]).unwrap();
+//! assert!(tags.contains(EXECUTABLE));
+//! ```
+
+pub use crate::tags::{
+    BINARY, BLANK, BLOCK_DEVICE, CHAR_DEVICE, CONTENT_MISMATCH, DIRECTORY, EXECUTABLE, FIFO, FILE,
+    HIDDEN, INVALID_UTF8, NON_EXECUTABLE, SCRIPT, SOCKET, SYMLINK, SYMLINK_LOOP, TEXT, Tag, TagSet,
+    UNKNOWN, VALID_UTF8, primary_language,
+};
+pub use crate::{
+    ContentErrorPolicy, ExecutablePolicy, FileIdentifier, IdentifyError, ShebangInfo, ShebangTuple,
+    file_is_text, is_executable, is_text, parse_shebang, parse_shebang_from_file,
+    tags_from_content, tags_from_filename, tags_from_interpreter, tags_from_path,
+};
@@ -1,14 +1,27 @@
 use once_cell::sync::Lazy;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
 
 pub const DIRECTORY: &str = "directory";
 pub const SYMLINK: &str = "symlink";
 pub const SOCKET: &str = "socket";
+pub const FIFO: &str = "fifo";
+pub const BLOCK_DEVICE: &str = "block-device";
+pub const CHAR_DEVICE: &str = "char-device";
 pub const FILE: &str = "file";
 pub const EXECUTABLE: &str = "executable";
 pub const NON_EXECUTABLE: &str = "non-executable";
 pub const TEXT: &str = "text";
 pub const BINARY: &str = "binary";
+pub const CONTENT_MISMATCH: &str = "content-mismatch";
+pub const SCRIPT: &str = "script";
+pub const VALID_UTF8: &str = "valid-utf8";
+pub const INVALID_UTF8: &str = "invalid-utf8";
+pub const SYMLINK_LOOP: &str = "symlink-loop";
+pub const BLANK: &str = "blank";
+pub const UNKNOWN: &str = "unknown";
+pub const HIDDEN: &str = "hidden";
 
 pub type TagSet = HashSet<&'static str>;
 
@@ -18,22 +31,385 @@ pub fn tags_from_array(tags: &[&'static str]) -> TagSet {
     tags.iter().cloned().collect()
 }
 
-pub static TYPE_TAGS: Lazy<TagSet> =
-    Lazy::new(|| HashSet::from([DIRECTORY, FILE, SYMLINK, SOCKET]));
+pub static TYPE_TAGS: Lazy<TagSet> = Lazy::new(|| {
+    HashSet::from([
+        DIRECTORY,
+        FILE,
+        SYMLINK,
+        SOCKET,
+        FIFO,
+        BLOCK_DEVICE,
+        CHAR_DEVICE,
+    ])
+});
 pub static MODE_TAGS: Lazy<TagSet> = Lazy::new(|| HashSet::from([EXECUTABLE, NON_EXECUTABLE]));
 pub static ENCODING_TAGS: Lazy<TagSet> = Lazy::new(|| HashSet::from([BINARY, TEXT]));
 
 /// Check if a tag is a file type tag (optimized with pattern matching)
-pub fn is_type_tag(tag: &str) -> bool {
-    matches!(tag, DIRECTORY | FILE | SYMLINK | SOCKET)
+pub fn is_type_tag<T: AsRef<str>>(tag: T) -> bool {
+    matches!(
+        tag.as_ref(),
+        DIRECTORY | FILE | SYMLINK | SOCKET | FIFO | BLOCK_DEVICE | CHAR_DEVICE
+    )
 }
 
 /// Check if a tag is a file mode tag (optimized with pattern matching)  
-pub fn is_mode_tag(tag: &str) -> bool {
-    matches!(tag, EXECUTABLE | NON_EXECUTABLE)
+pub fn is_mode_tag<T: AsRef<str>>(tag: T) -> bool {
+    matches!(tag.as_ref(), EXECUTABLE | NON_EXECUTABLE)
 }
 
 /// Check if a tag is an encoding tag (optimized with pattern matching)
-pub fn is_encoding_tag(tag: &str) -> bool {
-    matches!(tag, BINARY | TEXT)
+pub fn is_encoding_tag<T: AsRef<str>>(tag: T) -> bool {
+    matches!(tag.as_ref(), BINARY | TEXT)
+}
+
+/// The fixed structural tags: file types, modes and encodings.
+///
+/// Format and language tags remain plain strings, but these built-in tags
+/// can be compared with type safety instead of string literals.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags::Tag;
+/// use file_identify::tags_from_filename;
+///
+/// let tags = tags_from_filename("script.py");
+/// assert!(tags.contains(Tag::Text.as_str()));
+/// assert_eq!("binary".parse::<Tag>().unwrap(), Tag::Binary);
+/// assert!(Tag::Binary == *"binary");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tag {
+    File,
+    Directory,
+    Symlink,
+    Socket,
+    Fifo,
+    BlockDevice,
+    CharDevice,
+    Executable,
+    NonExecutable,
+    Text,
+    Binary,
+}
+
+impl Tag {
+    /// All built-in tags.
+    pub const ALL: [Tag; 11] = [
+        Tag::File,
+        Tag::Directory,
+        Tag::Symlink,
+        Tag::Socket,
+        Tag::Fifo,
+        Tag::BlockDevice,
+        Tag::CharDevice,
+        Tag::Executable,
+        Tag::NonExecutable,
+        Tag::Text,
+        Tag::Binary,
+    ];
+
+    /// The tag's string form, as found in identification results.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tag::File => FILE,
+            Tag::Directory => DIRECTORY,
+            Tag::Symlink => SYMLINK,
+            Tag::Socket => SOCKET,
+            Tag::Fifo => FIFO,
+            Tag::BlockDevice => BLOCK_DEVICE,
+            Tag::CharDevice => CHAR_DEVICE,
+            Tag::Executable => EXECUTABLE,
+            Tag::NonExecutable => NON_EXECUTABLE,
+            Tag::Text => TEXT,
+            Tag::Binary => BINARY,
+        }
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for Tag {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Tag {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Error returned when parsing a string that is not a built-in [`Tag`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("not a built-in tag: {0}")]
+pub struct ParseTagError(pub String);
+
+impl FromStr for Tag {
+    type Err = ParseTagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Tag::ALL
+            .into_iter()
+            .find(|tag| tag.as_str() == s)
+            .ok_or_else(|| ParseTagError(s.to_string()))
+    }
+}
+
+/// Check if a tag set contains at least one of the wanted tags.
+pub fn contains_any(tags: &TagSet, wanted: &[&str]) -> bool {
+    wanted.iter().any(|tag| tags.contains(tag))
+}
+
+/// Check if a tag set contains every one of the wanted tags.
+pub fn contains_all(tags: &TagSet, wanted: &[&str]) -> bool {
+    wanted.iter().all(|tag| tags.contains(tag))
+}
+
+/// A violation found by [`validate`]: more than one tag from a group that
+/// admits only one. Each variant holds the conflicting tags, sorted.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum TagInconsistency {
+    /// More than one file type tag, such as `file` and `directory`.
+    #[error("multiple type tags: {0:?}")]
+    MultipleTypes(Vec<&'static str>),
+    /// Both `executable` and `non-executable`.
+    #[error("multiple mode tags: {0:?}")]
+    MultipleModes(Vec<&'static str>),
+    /// Both `text` and `binary`.
+    #[error("multiple encoding tags: {0:?}")]
+    MultipleEncodings(Vec<&'static str>),
+}
+
+/// Check that a tag set has at most one type, mode and encoding tag.
+///
+/// Identification always returns consistent tags, but custom extension
+/// mappings or tag sets built by hand may not be. Groups are checked in the
+/// order type, mode, encoding, and the first violation is reported.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags::{TagInconsistency, TagSet, validate};
+///
+/// assert!(validate(&TagSet::from(["file", "text", "python"])).is_ok());
+/// assert_eq!(
+///     validate(&TagSet::from(["text", "binary"])),
+///     Err(TagInconsistency::MultipleEncodings(vec!["binary", "text"]))
+/// );
+/// ```
+pub fn validate(tags: &TagSet) -> Result<(), TagInconsistency> {
+    let conflicting = |group: &TagSet| {
+        let mut found: Vec<&'static str> = tags.intersection(group).copied().collect();
+        found.sort_unstable();
+        (found.len() > 1).then_some(found)
+    };
+
+    if let Some(found) = conflicting(&TYPE_TAGS) {
+        return Err(TagInconsistency::MultipleTypes(found));
+    }
+    if let Some(found) = conflicting(&MODE_TAGS) {
+        return Err(TagInconsistency::MultipleModes(found));
+    }
+    if let Some(found) = conflicting(&ENCODING_TAGS) {
+        return Err(TagInconsistency::MultipleEncodings(found));
+    }
+    Ok(())
+}
+
+/// Common synonyms of tags, mapped to the tag this crate uses.
+///
+/// Useful with [`canonicalize`] to normalize tags coming from other tools,
+/// or extension-style names such as `js` and `yml`.
+pub static DEFAULT_TAG_ALIASES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    HashMap::from([
+        ("cpp", "c++"),
+        ("golang", "go"),
+        ("htm", "html"),
+        ("jpg", "jpeg"),
+        ("js", "javascript"),
+        ("md", "markdown"),
+        ("py", "python"),
+        ("rb", "ruby"),
+        ("rs", "rust"),
+        ("tif", "tiff"),
+        ("typescript", "ts"),
+        ("yml", "yaml"),
+    ])
+});
+
+/// Replace each tag that has an alias with its canonical form.
+///
+/// Tags without an entry in `aliases` are kept as they are. Aliases are not
+/// applied transitively.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags::{DEFAULT_TAG_ALIASES, TagSet, canonicalize};
+///
+/// let tags = TagSet::from(["text", "js"]);
+/// let canonical = canonicalize(&tags, &DEFAULT_TAG_ALIASES);
+/// assert_eq!(canonical, TagSet::from(["text", "javascript"]));
+/// ```
+pub fn canonicalize(tags: &TagSet, aliases: &HashMap<&str, &'static str>) -> TagSet {
+    tags.iter()
+        .map(|&tag| aliases.get(tag).copied().unwrap_or(tag))
+        .collect()
+}
+
+/// Language tags in the order [`primary_language`] prefers them.
+///
+/// Dialects come before the family they belong to (`bash` before `shell`,
+/// `tsx` before `ts`, `objective-c++` before `c++`), and programming
+/// languages come before markup and data formats.
+pub static LANGUAGE_PRIORITY: &[&str] = &[
+    // Shells: specific shells before the generic `shell` tag
+    "bash",
+    "zsh",
+    "fish",
+    "ksh",
+    "tcsh",
+    "csh",
+    "dash",
+    "ash",
+    "sh",
+    "shell",
+    "powershell",
+    "batch",
+    // Programming languages
+    "cython",
+    "python",
+    "ruby",
+    "perl",
+    "php",
+    "lua",
+    "tsx",
+    "ts",
+    "jsx",
+    "javascript",
+    "coffee",
+    "rust",
+    "go",
+    "objective-c++",
+    "objective-c",
+    "c",
+    "c++",
+    "c#",
+    "f#",
+    "java",
+    "kotlin",
+    "scala",
+    "groovy",
+    "clojurescript",
+    "clojure",
+    "swift",
+    "dart",
+    "elixir",
+    "erlang",
+    "literate-haskell",
+    "haskell",
+    "ocaml",
+    "elm",
+    "julia",
+    "r",
+    "nim",
+    "zig",
+    "crystal",
+    "gleam",
+    "purescript",
+    "idris",
+    "lean",
+    "solidity",
+    "sql",
+    "gdscript",
+    "fortran",
+    "pascal",
+    "cuda",
+    "metal",
+    "asm",
+    "vb",
+    "vhdl",
+    "system-verilog",
+    "verilog",
+    "scheme",
+    "awk",
+    "expect",
+    "vim",
+    "nix",
+    "terraform",
+    "hcl",
+    "jsonnet",
+    "cue",
+    "graphql",
+    "prisma",
+    // Web components and markup
+    "vue",
+    "svelte",
+    "astro",
+    "html",
+    "xhtml",
+    "css",
+    "scss",
+    "sass",
+    "less",
+    "stylus",
+    "markdown",
+    "mdx",
+    "rst",
+    "asciidoc",
+    "tex",
+    // Build files
+    "dockerfile",
+    "makefile",
+    "cmake",
+    "meson",
+    "bazel",
+    // Data formats
+    "json5",
+    "json",
+    "yaml",
+    "toml",
+    "xml",
+    "ini",
+];
+
+/// Pick the single most specific language tag from an identification result.
+///
+/// Editors and syntax highlighters usually want one language rather than a
+/// set. The first tag of [`LANGUAGE_PRIORITY`] present in `tags` wins, so:
+/// - Type, mode and encoding tags such as `file` or `text` are never returned.
+/// - Versioned tags like `python3` are skipped in favor of `python`.
+/// - A specific dialect wins over its family, e.g. `bash` over `shell`.
+/// - Project files are tagged by format rather than by the language they
+///   serve, so `Cargo.toml` reports `toml`, not `rust`.
+///
+/// Returns `None` if no language tag is present, as for plain text files.
+///
+/// # Examples
+///
+/// ```rust
+/// use file_identify::tags::primary_language;
+/// use file_identify::tags_from_filename;
+///
+/// assert_eq!(primary_language(&tags_from_filename("setup.py")), Some("python"));
+/// assert_eq!(primary_language(&tags_from_filename("notes.txt")), None);
+/// ```
+pub fn primary_language(tags: &TagSet) -> Option<&'static str> {
+    LANGUAGE_PRIORITY
+        .iter()
+        .copied()
+        .find(|tag| tags.contains(tag))
 }
@@ -0,0 +1,60 @@
+#![cfg(feature = "flate2")]
+
+use file_identify::FileIdentifier;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io::Write;
+use tempfile::tempdir;
+
+fn gzip(content: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn test_peek_inside_gzipped_script() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("tool.gz");
+    fs::write(&path, gzip(b"#!/usr/bin/env python3\nprint('hello')\n")).unwrap();
+
+    let identifier = FileIdentifier::new().peek_inside_archives();
+    let tags = identifier.identify(&path).unwrap();
+    assert!(tags.contains("gzip"));
+    assert!(tags.contains("binary"));
+    assert!(tags.contains("python"));
+    assert!(!tags.contains("text"));
+
+    // Not analyzed unless opted in
+    let tags = FileIdentifier::new().identify(&path).unwrap();
+    assert!(!tags.contains("python"));
+}
+
+#[test]
+fn test_peek_inside_uses_inner_filename() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("data.json.gz");
+    fs::write(&path, gzip(b"{\"key\": \"value\"}")).unwrap();
+
+    let tags = FileIdentifier::new()
+        .peek_inside_archives()
+        .identify(&path)
+        .unwrap();
+    assert!(tags.contains("gzip"));
+    assert!(tags.contains("json"));
+}
+
+#[test]
+fn test_peek_inside_skips_tarballs() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("scripts.tar.gz");
+    fs::write(&path, gzip(b"#!/bin/sh\necho hi\n")).unwrap();
+
+    let tags = FileIdentifier::new()
+        .peek_inside_archives()
+        .identify(&path)
+        .unwrap();
+    assert!(tags.contains("gzip"));
+    assert!(!tags.contains("shell"));
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrow, clippy::needless_borrows_for_generic_args)]
+
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::process::Command;
@@ -50,6 +52,24 @@ fn test_cli_filename_only() {
     assert!(!tags.contains(&"file".to_string()));
 }
 
+#[test]
+fn test_cli_print0() {
+    let output = Command::new(get_cli_path())
+        .args(["--filename-only", "--print0", "test.py"])
+        .output()
+        .expect("Failed to execute CLI");
+
+    assert!(output.status.success());
+    let record = output
+        .stdout
+        .strip_suffix(b"\0")
+        .expect("record should be NUL-terminated");
+    assert!(!record.contains(&b'\n'));
+
+    let tags: Vec<String> = serde_json::from_slice(record).unwrap();
+    assert!(tags.contains(&"python".to_string()));
+}
+
 #[test]
 fn test_cli_file_not_found() {
     let output = Command::new(get_cli_path())
@@ -1,4 +1,6 @@
-use file_identify::extensions::{EXTENSION_TAGS, EXTENSIONS_NEED_BINARY_CHECK_TAGS, NAME_TAGS};
+use file_identify::extensions::{
+    EXTENSION_TAGS, EXTENSIONS_NEED_BINARY_CHECK_TAGS, NAME_TAGS, extensions_for_tag,
+};
 use file_identify::tags::tags_from_array;
 use std::collections::HashSet;
 
@@ -62,3 +64,38 @@ fn test_mutually_exclusive_check_types() {
         intersection
     );
 }
+
+#[test]
+fn test_svg_is_textual_image() {
+    let tags = tags_from_array(EXTENSION_TAGS["svg"]);
+    assert_eq!(tags, HashSet::from(["text", "xml", "svg", "image"]));
+    assert!(!tags.contains("binary"));
+
+    // An image tag is not a binary tag: identification agrees with the content
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("logo.svg");
+    std::fs::write(
+        &path,
+        "<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>\n",
+    )
+    .unwrap();
+    let tags = file_identify::FileIdentifier::new()
+        .flag_conflicts()
+        .identify(&path)
+        .unwrap();
+    assert!(tags.is_superset(&HashSet::from(["text", "xml", "svg", "image"])));
+    assert!(!tags.contains("binary"));
+    assert!(!tags.contains("content-mismatch"));
+}
+
+#[test]
+fn test_extensions_for_tag() {
+    let python = extensions_for_tag("python");
+    assert!(python.contains(&"py"));
+    assert!(python.is_sorted());
+    assert!(!python.contains(&"js"));
+
+    // Extensions whose encoding depends on content are included
+    assert!(extensions_for_tag("plist").contains(&"plist"));
+    assert!(extensions_for_tag("no-such-tag").is_empty());
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "sha2")]
+
+use file_identify::FileIdentifier;
+use sha2::{Digest, Sha256};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_identify_with_hash_covers_whole_file() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("big.py");
+    let content: Vec<u8> = (0..100_000).map(|i| b"abcdefgh\n"[i % 9]).collect();
+    fs::write(&path, &content).unwrap();
+
+    let identifier = FileIdentifier::new();
+    let (tags, hash) = identifier.identify_with_hash(&path).unwrap();
+    assert_eq!(tags, identifier.identify(&path).unwrap());
+    assert_eq!(hash, <[u8; 32]>::from(Sha256::digest(&content)));
+
+    // The hash covers the whole file even when content analysis is skipped
+    let identifier = FileIdentifier::new().max_content_bytes(10);
+    let (_, limited_hash) = identifier.identify_with_hash(&path).unwrap();
+    assert_eq!(limited_hash, hash);
+}
+
+#[test]
+fn test_identify_with_hash_known_digest() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("abc");
+    fs::write(&path, "abc").unwrap();
+
+    let (tags, hash) = FileIdentifier::new().identify_with_hash(&path).unwrap();
+    assert!(tags.contains("text"));
+    let hex: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+    assert_eq!(
+        hex,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn test_identify_with_hash_directory() {
+    let dir = tempdir().unwrap();
+
+    let (tags, hash) = FileIdentifier::new()
+        .identify_with_hash(dir.path())
+        .unwrap();
+    assert!(tags.contains("directory"));
+    assert_eq!(hash, <[u8; 32]>::from(Sha256::digest(b"")));
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "tracing")]
+
+use file_identify::FileIdentifier;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::tempdir;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+// Minimal subscriber that enables every callsite and counts events
+struct CountingSubscriber {
+    events: Arc<AtomicUsize>,
+}
+
+impl Subscriber for CountingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {
+        self.events.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn test_tracing_does_not_change_tags() {
+    let dir = tempdir().unwrap();
+    let script_path = dir.path().join("script");
+    fs::write(&script_path, "#!/usr/bin/env python3\nprint('hello')").unwrap();
+    let mut perms = fs::metadata(&script_path).unwrap().permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&script_path, perms).unwrap();
+
+    let identifier = FileIdentifier::new();
+    let untraced = identifier.identify(&script_path).unwrap();
+
+    let events = Arc::new(AtomicUsize::new(0));
+    let subscriber = CountingSubscriber {
+        events: Arc::clone(&events),
+    };
+    let traced = tracing::subscriber::with_default(subscriber, || {
+        identifier.identify(&script_path).unwrap()
+    });
+
+    assert_eq!(traced, untraced);
+    assert!(events.load(Ordering::SeqCst) > 0);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "zip")]
+
+use file_identify::FileIdentifier;
+use std::fs;
+use std::io::{Cursor, Write};
+use tempfile::tempdir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+fn zip_with_entries(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for (name, content) in entries {
+        writer.start_file(*name, options).unwrap();
+        writer.write_all(content).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_inspect_zip_jar() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app");
+    let jar = zip_with_entries(&[
+        ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n"),
+        ("com/example/Main.class", b"\xca\xfe\xba\xbe"),
+    ]);
+    fs::write(&path, jar).unwrap();
+
+    let tags = FileIdentifier::new().inspect_zip().identify(&path).unwrap();
+    assert!(tags.contains("zip"));
+    assert!(tags.contains("jar"));
+    assert!(tags.contains("binary"));
+
+    // Not inspected unless opted in
+    let tags = FileIdentifier::new().identify(&path).unwrap();
+    assert!(tags.contains("zip"));
+    assert!(!tags.contains("jar"));
+}
+
+#[test]
+fn test_inspect_zip_docx() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("report.docx");
+    let docx = zip_with_entries(&[
+        ("[Content_Types].xml", b"<?xml version=\"1.0\"?><Types/>"),
+        ("word/document.xml", b"<?xml version=\"1.0\"?><w:document/>"),
+    ]);
+    fs::write(&path, docx).unwrap();
+
+    let tags = FileIdentifier::new().inspect_zip().identify(&path).unwrap();
+    assert!(tags.contains("zip"));
+    assert!(tags.contains("ooxml"));
+    assert!(!tags.contains("jar"));
+}
+
+#[test]
+fn test_inspect_zip_apk_wins_over_jar_manifest() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.bin");
+    let apk = zip_with_entries(&[
+        ("AndroidManifest.xml", b"\x03\x00\x08\x00"),
+        ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n"),
+    ]);
+    fs::write(&path, apk).unwrap();
+
+    let tags = FileIdentifier::new().inspect_zip().identify(&path).unwrap();
+    assert!(tags.contains("apk"));
+    assert!(!tags.contains("jar"));
+}
+
+#[test]
+fn test_inspect_zip_plain_archive() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("bundle.zip");
+    fs::write(&path, zip_with_entries(&[("README.txt", b"hello\n")])).unwrap();
+
+    let tags = FileIdentifier::new().inspect_zip().identify(&path).unwrap();
+    assert!(tags.contains("zip"));
+    assert!(!tags.contains("jar"));
+    assert!(!tags.contains("ooxml"));
+    assert!(!tags.contains("apk"));
+}